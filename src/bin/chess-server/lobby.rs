@@ -0,0 +1,404 @@
+use crate::table::{self, ClientId, Outcome, Seat, Table};
+use chess::piece::PieceColour;
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::mpsc::Sender;
+
+/// A connected client. Lines for them are queued on `outgoing` and written by their
+/// connection's own thread, so a slow reader never holds up the lobby.
+struct Client {
+    name: String,
+    outgoing: Sender<String>,
+}
+
+/// An open invitation to play. `colour` is the side the seeker wants, or `None` for either.
+struct Seek {
+    id: u64,
+    client: ClientId,
+    minutes: u64,
+    colour: Option<PieceColour>,
+}
+
+/// Everything the server knows: who is connected, the open seeks and the games being played.
+pub struct Lobby {
+    clients: HashMap<ClientId, Client>,
+    seeks: Vec<Seek>,
+    tables: HashMap<u64, Table>,
+    next_id: u64,
+}
+
+impl Lobby {
+    pub fn new() -> Self {
+        Lobby {
+            clients: HashMap::new(),
+            seeks: Vec::new(),
+            tables: HashMap::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn connect(&mut self, outgoing: Sender<String>) -> ClientId {
+        let client: ClientId = self.new_id();
+        self.clients.insert(
+            client,
+            Client {
+                name: format!("guest{}", client),
+                outgoing,
+            },
+        );
+        self.send(client, &format!("hello {}", self.name(client)));
+        client
+    }
+
+    /// Forgets a dropped connection. Its games carry on, clocks running, until the player
+    /// reconnects or runs out of time.
+    pub fn disconnect(&mut self, client: ClientId) {
+        self.clients.remove(&client);
+        self.seeks.retain(|seek| seek.client != client);
+        let mut left: Vec<(u64, PieceColour)> = Vec::new();
+        for (id, table) in self.tables.iter_mut() {
+            table.spectators.retain(|spectator| *spectator != client);
+            if let Some(colour) = table.colour_of(client) {
+                table.seat_mut(colour).client = None;
+                left.push((*id, colour));
+            }
+        }
+        for (id, colour) in left {
+            self.broadcast(id, &format!("left {} {}", id, colour_name(colour)));
+        }
+    }
+
+    /// Ends every game in which the side to move has run out of time.
+    pub fn check_clocks(&mut self) {
+        let flagged: Vec<(u64, PieceColour)> = self
+            .tables
+            .iter()
+            .filter_map(|(id, table)| table.flagged().map(|colour| (*id, colour)))
+            .collect();
+        for (id, colour) in flagged {
+            self.finish(
+                id,
+                Outcome {
+                    result: table::win_for(colour.opponent()),
+                    reason: "time forfeit",
+                },
+            );
+        }
+    }
+
+    pub fn handle(&mut self, client: ClientId, line: &str) {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let outcome: Result<(), &'static str> = match words.as_slice() {
+            [] => Ok(()),
+            ["name", name] => {
+                if let Some(entry) = self.clients.get_mut(&client) {
+                    entry.name = name.to_string();
+                }
+                Ok(())
+            }
+            ["seek", minutes, colour @ ..] => self.seek(client, minutes, colour.first().copied()),
+            ["cancel"] => {
+                self.seeks.retain(|seek| seek.client != client);
+                Ok(())
+            }
+            ["seeks"] => {
+                self.list_seeks(client);
+                Ok(())
+            }
+            ["join", seek] => self.join(client, seek),
+            ["games"] => {
+                self.list_games(client);
+                Ok(())
+            }
+            ["watch", game] => self.watch(client, game),
+            ["unwatch", game] => {
+                if let Some(table) = self.table_id(game).and_then(|id| self.tables.get_mut(&id)) {
+                    table.spectators.retain(|spectator| *spectator != client);
+                }
+                Ok(())
+            }
+            ["move", game, notation] => self.play(client, game, notation, false),
+            ["move", game, notation, "draw"] => self.play(client, game, notation, true),
+            ["accept", game] => self.accept(client, game),
+            ["resign", game] => self.resign(client, game),
+            ["reconnect", game, key] => self.reconnect(client, game, key),
+            _ => Err("unknown command"),
+        };
+        if let Err(message) = outcome {
+            self.send(client, &format!("error {}", message));
+        }
+    }
+
+    fn seek(
+        &mut self,
+        client: ClientId,
+        minutes: &str,
+        colour: Option<&str>,
+    ) -> Result<(), &'static str> {
+        let minutes: u64 = match minutes.parse() {
+            Ok(minutes) if minutes > 0 => minutes,
+            _ => return Err("bad time control"),
+        };
+        let colour: Option<PieceColour> = match colour {
+            None | Some("random") => None,
+            Some("white") => Some(PieceColour::White),
+            Some("black") => Some(PieceColour::Black),
+            Some(_) => return Err("colour must be white, black or random"),
+        };
+        let id: u64 = self.new_id();
+        self.seeks.push(Seek {
+            id,
+            client,
+            minutes,
+            colour,
+        });
+        self.send(client, &format!("seeking {}", id));
+        Ok(())
+    }
+
+    fn list_seeks(&mut self, client: ClientId) {
+        let lines: Vec<String> = self
+            .seeks
+            .iter()
+            .map(|seek| {
+                format!(
+                    "seek {} {} {} {}",
+                    seek.id,
+                    self.name(seek.client),
+                    seek.minutes,
+                    seek.colour.map_or("random", colour_name)
+                )
+            })
+            .collect();
+        for line in lines {
+            self.send(client, &line);
+        }
+        self.send(client, "end");
+    }
+
+    fn list_games(&mut self, client: ClientId) {
+        let lines: Vec<String> = self
+            .tables
+            .iter()
+            .map(|(id, table)| {
+                format!(
+                    "game {} {} {} {} {}",
+                    id,
+                    table.white.name,
+                    table.black.name,
+                    table.minutes,
+                    table.move_count()
+                )
+            })
+            .collect();
+        for line in lines {
+            self.send(client, &line);
+        }
+        self.send(client, "end");
+    }
+
+    fn join(&mut self, client: ClientId, seek: &str) -> Result<(), &'static str> {
+        let index: usize = seek
+            .parse::<u64>()
+            .ok()
+            .and_then(|id| self.seeks.iter().position(|seek| seek.id == id))
+            .ok_or("no such seek")?;
+        if self.seeks[index].client == client {
+            return Err("cannot accept your own seek");
+        }
+        let seek: Seek = self.seeks.remove(index);
+
+        let seeker_colour: PieceColour = seek.colour.unwrap_or(if random().is_multiple_of(2) {
+            PieceColour::White
+        } else {
+            PieceColour::Black
+        });
+        let seeker: Seat = self.new_seat(seek.client);
+        let joiner: Seat = self.new_seat(client);
+        let (white, black): (Seat, Seat) = match seeker_colour {
+            PieceColour::White => (seeker, joiner),
+            PieceColour::Black => (joiner, seeker),
+        };
+
+        let id: u64 = self.new_id();
+        self.tables
+            .insert(id, Table::new(seek.minutes, white, black));
+        for colour in [PieceColour::White, PieceColour::Black] {
+            self.send_seat(id, colour);
+        }
+        Ok(())
+    }
+
+    fn watch(&mut self, client: ClientId, game: &str) -> Result<(), &'static str> {
+        let id: u64 = self.table_id(game).ok_or("no such game")?;
+        let table: &mut Table = self.tables.get_mut(&id).unwrap();
+        if !table.spectators.contains(&client) {
+            table.spectators.push(client);
+        }
+        let line: String = format!("position {} {}", id, table.position_text());
+        self.send(client, &line);
+        Ok(())
+    }
+
+    fn play(
+        &mut self,
+        client: ClientId,
+        game: &str,
+        notation: &str,
+        draw_offer: bool,
+    ) -> Result<(), &'static str> {
+        let (id, colour): (u64, PieceColour) = self.seated(client, game)?;
+        if self.tables[&id].flagged().is_some() {
+            self.check_clocks();
+            return Ok(());
+        }
+
+        let table: &mut Table = self.tables.get_mut(&id).unwrap();
+        let (record, outcome) = table.play(colour, notation, draw_offer)?;
+        let mut line: String = format!(
+            "moved {} {} {} {}",
+            id,
+            record.coordinate_move.to_notation(),
+            record.san,
+            table.clock_text()
+        );
+        if draw_offer {
+            line.push_str(" draw");
+        }
+        self.broadcast(id, &line);
+        if let Some(outcome) = outcome {
+            self.finish(id, outcome);
+        }
+        Ok(())
+    }
+
+    fn accept(&mut self, client: ClientId, game: &str) -> Result<(), &'static str> {
+        let (id, colour): (u64, PieceColour) = self.seated(client, game)?;
+        if self.tables[&id].draw_offer != Some(colour.opponent()) {
+            return Err("there is no draw offer to accept");
+        }
+        self.finish(id, table::draw("agreement"));
+        Ok(())
+    }
+
+    fn resign(&mut self, client: ClientId, game: &str) -> Result<(), &'static str> {
+        let (id, colour): (u64, PieceColour) = self.seated(client, game)?;
+        self.finish(
+            id,
+            Outcome {
+                result: table::win_for(colour.opponent()),
+                reason: "resignation",
+            },
+        );
+        Ok(())
+    }
+
+    /// Gives a player who lost their connection their seat back.
+    fn reconnect(&mut self, client: ClientId, game: &str, key: &str) -> Result<(), &'static str> {
+        let id: u64 = self.table_id(game).ok_or("no such game")?;
+        let table: &mut Table = self.tables.get_mut(&id).unwrap();
+        let colour: PieceColour = [PieceColour::White, PieceColour::Black]
+            .into_iter()
+            .find(|colour| table.seat(*colour).key.to_string() == key)
+            .ok_or("wrong key")?;
+        table.seat_mut(colour).client = Some(client);
+
+        self.send_seat(id, colour);
+        let line: String = format!("position {} {}", id, self.tables[&id].position_text());
+        self.send(client, &line);
+        self.broadcast(id, &format!("returned {} {}", id, colour_name(colour)));
+        Ok(())
+    }
+
+    fn finish(&mut self, id: u64, outcome: Outcome) {
+        self.broadcast(
+            id,
+            &format!("over {} {} {}", id, outcome.result, outcome.reason),
+        );
+        self.tables.remove(&id);
+    }
+
+    /// Tells a player about the game they are seated in, including the key to reconnect with.
+    fn send_seat(&mut self, id: u64, colour: PieceColour) {
+        let table: &Table = &self.tables[&id];
+        let seat: &Seat = table.seat(colour);
+        if let Some(client) = seat.client {
+            let line: String = format!(
+                "started {} {} {} {} {} {}",
+                id,
+                colour_name(colour),
+                seat.key,
+                table.white.name,
+                table.black.name,
+                table.minutes
+            );
+            self.send(client, &line);
+        }
+    }
+
+    fn seated(&self, client: ClientId, game: &str) -> Result<(u64, PieceColour), &'static str> {
+        let id: u64 = self.table_id(game).ok_or("no such game")?;
+        let colour: PieceColour = self.tables[&id]
+            .colour_of(client)
+            .ok_or("you are not playing in this game")?;
+        Ok((id, colour))
+    }
+
+    fn table_id(&self, game: &str) -> Option<u64> {
+        game.parse::<u64>()
+            .ok()
+            .filter(|id| self.tables.contains_key(id))
+    }
+
+    fn new_seat(&self, client: ClientId) -> Seat {
+        Seat {
+            name: self.name(client),
+            client: Some(client),
+            key: random(),
+        }
+    }
+
+    fn new_id(&mut self) -> u64 {
+        let id: u64 = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn name(&self, client: ClientId) -> String {
+        self.clients
+            .get(&client)
+            .map_or_else(String::new, |entry| entry.name.clone())
+    }
+
+    fn broadcast(&mut self, id: u64, line: &str) {
+        let audience: Vec<ClientId> = match self.tables.get(&id) {
+            Some(table) => table.audience(),
+            None => return,
+        };
+        for client in audience {
+            self.send(client, line);
+        }
+    }
+
+    fn send(&mut self, client: ClientId, line: &str) {
+        if let Some(entry) = self.clients.get_mut(&client) {
+            // a dead connection is cleaned up when its reading thread sees the end of input
+            let _ = entry.outgoing.send(line.to_string());
+        }
+    }
+}
+
+fn colour_name(colour: PieceColour) -> &'static str {
+    match colour {
+        PieceColour::White => "white",
+        PieceColour::Black => "black",
+    }
+}
+
+/// A random number from the standard library's per-process hash keys, which is plenty for
+/// picking colours. It is not a secure source: reconnect keys stop players from taking the
+/// wrong seat by mistake, but are not secrets that would stop someone set on guessing one.
+fn random() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
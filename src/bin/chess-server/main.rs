@@ -0,0 +1,119 @@
+//! A server hosting any number of games at once. Clients connect over TCP and send one command
+//! per line:
+//!
+//! ```text
+//! name <name>                  the name shown to other players (default guest<n>)
+//! seek <minutes> [colour]      offers a game; colour is white, black or random
+//! cancel                       withdraws your seeks
+//! seeks                        lists open seeks
+//! join <seek>                  accepts a seek, starting the game
+//! games                        lists games in progress
+//! watch <game>                 spectates a game
+//! unwatch <game>
+//! move <game> <move> [draw]    plays a move in any notation the terminal game accepts
+//! accept <game>                accepts a draw offer
+//! resign <game>
+//! reconnect <game> <key>       takes your seat back after losing the connection
+//! ```
+//!
+//! The server answers with:
+//!
+//! ```text
+//! hello <name>
+//! seeking <seek>
+//! seek <seek> <name> <minutes> <colour>            one per seek, then `end`
+//! game <game> <white> <black> <minutes> <moves>    one per game, then `end`
+//! started <game> <colour> <key> <white> <black> <minutes>
+//! position <game> <fen> <white ms> <black ms> <moves...>
+//! moved <game> <move> <san> <white ms> <black ms> [draw]
+//! left <game> <colour>
+//! returned <game> <colour>
+//! over <game> <result> <reason>
+//! error <message>
+//! ```
+//!
+//! `started` goes to both players when a seek is accepted and again after a reconnect, and
+//! `position` to spectators when they start watching. `moved`, `left`, `returned` and `over`
+//! go to both players and every spectator. A player who drops out keeps their seat, with their
+//! clock still running, until they reconnect with the key from `started`.
+
+mod lobby;
+mod table;
+
+use lobby::Lobby;
+use std::env::args;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use table::ClientId;
+
+const DEFAULT_PORT: u16 = 7878;
+const CLOCK_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+fn main() {
+    let port: u16 = args()
+        .nth(1)
+        .and_then(|port| port.parse().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let listener: TcpListener = match TcpListener::bind(("0.0.0.0", port)) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Cannot listen on port {}: {}", port, error);
+            process::exit(1);
+        }
+    };
+    println!("Listening on port {}", port);
+
+    let lobby: Arc<Mutex<Lobby>> = Arc::new(Mutex::new(Lobby::new()));
+    let clock_lobby: Arc<Mutex<Lobby>> = Arc::clone(&lobby);
+    thread::spawn(move || loop {
+        thread::sleep(CLOCK_CHECK_INTERVAL);
+        clock_lobby.lock().unwrap().check_clocks();
+    });
+
+    for stream in listener.incoming() {
+        let stream: TcpStream = match stream {
+            Ok(stream) => stream,
+            Err(_) => continue,
+        };
+        let lobby: Arc<Mutex<Lobby>> = Arc::clone(&lobby);
+        thread::spawn(move || {
+            if let Err(error) = serve(stream, &lobby) {
+                eprintln!("Connection error: {}", error);
+            }
+        });
+    }
+}
+
+fn serve(stream: TcpStream, lobby: &Mutex<Lobby>) -> io::Result<()> {
+    let reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+    let (outgoing, lines): (Sender<String>, Receiver<String>) = mpsc::channel();
+    thread::spawn(move || write_lines(stream, lines));
+    let client: ClientId = lobby.lock().unwrap().connect(outgoing);
+    let mut result: io::Result<()> = Ok(());
+    for line in reader.lines() {
+        match line {
+            Ok(line) => lobby.lock().unwrap().handle(client, line.trim()),
+            Err(error) => {
+                result = Err(error);
+                break;
+            }
+        }
+    }
+    lobby.lock().unwrap().disconnect(client);
+    result
+}
+
+/// Writes the lines queued for one client until the lobby forgets them or the connection fails,
+/// away from the lobby's lock so that a client who stops reading only holds up their own lines.
+fn write_lines(mut stream: TcpStream, lines: Receiver<String>) {
+    for line in lines {
+        if writeln!(stream, "{}", line).is_err() {
+            break;
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use chess::{
+    board::Board,
+    pgn::MoveRecord,
+    piece::{PieceColour, PieceType},
+    position::{CoordinateMove, MoveResult},
+};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub type ClientId = u64;
+
+/// One side of a game. `client` is empty while the player is disconnected; `key` lets them
+/// take the seat back.
+pub struct Seat {
+    pub name: String,
+    pub client: Option<ClientId>,
+    pub key: u64,
+}
+
+/// How a game ended, as a PGN result and the reason shown to the players.
+pub struct Outcome {
+    pub result: &'static str,
+    pub reason: &'static str,
+}
+
+/// A game in progress on the server.
+///
+/// The server has no clock threads: each side's time is only brought up to date when they move,
+/// and the side to move has whatever was left minus the time since the last move.
+pub struct Table {
+    pub minutes: u64,
+    pub white: Seat,
+    pub black: Seat,
+    pub spectators: Vec<ClientId>,
+    pub draw_offer: Option<PieceColour>,
+    board: Board,
+    turn: PieceColour,
+    moves: Vec<MoveRecord>,
+    position_counts: HashMap<String, u32>,
+    white_clock: Duration,
+    black_clock: Duration,
+    turn_started: Instant,
+}
+
+impl Table {
+    pub fn new(minutes: u64, white: Seat, black: Seat) -> Self {
+        let board: Board = Board::new();
+        let mut position_counts: HashMap<String, u32> = HashMap::new();
+        position_counts.insert(board.position_key(PieceColour::White), 1);
+        Table {
+            minutes,
+            white,
+            black,
+            spectators: Vec::new(),
+            draw_offer: None,
+            board,
+            turn: PieceColour::White,
+            moves: Vec::new(),
+            position_counts,
+            white_clock: Duration::from_secs(minutes * 60),
+            black_clock: Duration::from_secs(minutes * 60),
+            turn_started: Instant::now(),
+        }
+    }
+
+    pub fn seat(&self, colour: PieceColour) -> &Seat {
+        match colour {
+            PieceColour::White => &self.white,
+            PieceColour::Black => &self.black,
+        }
+    }
+
+    pub fn seat_mut(&mut self, colour: PieceColour) -> &mut Seat {
+        match colour {
+            PieceColour::White => &mut self.white,
+            PieceColour::Black => &mut self.black,
+        }
+    }
+
+    /// The colour `client` is playing, if they are seated at this table.
+    pub fn colour_of(&self, client: ClientId) -> Option<PieceColour> {
+        if self.white.client == Some(client) {
+            Some(PieceColour::White)
+        } else if self.black.client == Some(client) {
+            Some(PieceColour::Black)
+        } else {
+            None
+        }
+    }
+
+    /// Everyone who hears about moves: both players and the spectators.
+    pub fn audience(&self) -> Vec<ClientId> {
+        let mut audience: Vec<ClientId> = self.spectators.clone();
+        audience.extend(self.white.client);
+        audience.extend(self.black.client);
+        audience
+    }
+
+    pub fn move_count(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn remaining(&self, colour: PieceColour) -> Duration {
+        let clock: Duration = match colour {
+            PieceColour::White => self.white_clock,
+            PieceColour::Black => self.black_clock,
+        };
+        if colour == self.turn {
+            clock.saturating_sub(self.turn_started.elapsed())
+        } else {
+            clock
+        }
+    }
+
+    /// `<white ms> <black ms>`, as sent with every move.
+    pub fn clock_text(&self) -> String {
+        format!(
+            "{} {}",
+            self.remaining(PieceColour::White).as_millis(),
+            self.remaining(PieceColour::Black).as_millis()
+        )
+    }
+
+    /// The position and every move so far, for spectators and players who reconnect.
+    pub fn position_text(&self) -> String {
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|record| record.coordinate_move.to_notation())
+            .collect();
+        format!(
+            "{} {} {}",
+            self.board.to_fen(self.turn, self.moves.len() / 2 + 1),
+            self.clock_text(),
+            moves.join(" ")
+        )
+        .trim_end()
+        .to_string()
+    }
+
+    /// The side whose clock has run out, if any.
+    pub fn flagged(&self) -> Option<PieceColour> {
+        if self.remaining(self.turn).is_zero() {
+            Some(self.turn)
+        } else {
+            None
+        }
+    }
+
+    /// Plays a move for `colour`, returning its record and how the game ended if it did.
+    /// Pawns reaching the last rank become queens unless the move names another piece.
+    pub fn play(
+        &mut self,
+        colour: PieceColour,
+        notation: &str,
+        draw_offer: bool,
+    ) -> Result<(MoveRecord, Option<Outcome>), &'static str> {
+        if colour != self.turn {
+            return Err("it is not your turn");
+        }
+
+        let board_before_move: Board = self.board.clone();
+        let move_result: MoveResult = match self.board.play_notation(colour, notation) {
+            Ok(MoveResult::PromotionAvailable(square)) => {
+                self.board.promote(square, colour, PieceType::Queen);
+                MoveResult::Success
+            }
+            Ok(move_result @ (MoveResult::Success | MoveResult::Draw)) => move_result,
+            Ok(MoveResult::AmbiguousMove) => return Err("more than one piece can make this move"),
+            Ok(MoveResult::Checked) => return Err("cannot make this move due to check"),
+            Ok(MoveResult::MissingPiece) => return Err("no piece can make this move"),
+            Ok(MoveResult::PiecePinned) => return Err("this piece is pinned"),
+            Ok(_) => return Err("this move is not legal"),
+            Err(_) => return Err("notation is invalid"),
+        };
+
+        let elapsed: Duration = self.turn_started.elapsed();
+        let clock: &mut Duration = match colour {
+            PieceColour::White => &mut self.white_clock,
+            PieceColour::Black => &mut self.black_clock,
+        };
+        *clock = clock.saturating_sub(elapsed);
+        self.turn_started = Instant::now();
+
+        let played: CoordinateMove = self.board.last_move().unwrap();
+        let record: MoveRecord = MoveRecord {
+            coordinate_move: played,
+            san: board_before_move.san(colour, &played),
+            draw_offer,
+        };
+        self.moves.push(record.clone());
+        self.draw_offer = if draw_offer { Some(colour) } else { None };
+        self.turn = colour.opponent();
+
+        let repetitions: &mut u32 = self
+            .position_counts
+            .entry(self.board.position_key(self.turn))
+            .or_insert(0);
+        *repetitions += 1;
+        let repetitions: u32 = *repetitions;
+
+        let outcome: Option<Outcome> = if self.board.is_mate(self.turn) {
+            Some(Outcome {
+                result: win_for(colour),
+                reason: "checkmate",
+            })
+        } else if self.board.is_stalemate(self.turn) {
+            Some(draw("stalemate"))
+        } else if move_result == MoveResult::Draw {
+            Some(draw("fifty-move rule"))
+        } else if repetitions >= 3 {
+            Some(draw("threefold repetition"))
+        } else {
+            None
+        };
+        Ok((record, outcome))
+    }
+}
+
+pub fn win_for(colour: PieceColour) -> &'static str {
+    match colour {
+        PieceColour::White => "1-0",
+        PieceColour::Black => "0-1",
+    }
+}
+
+pub fn draw(reason: &'static str) -> Outcome {
+    Outcome {
+        result: "1/2-1/2",
+        reason,
+    }
+}
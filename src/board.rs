@@ -2,7 +2,9 @@ use std::collections::HashMap;
 
 use crate::{
     piece::{Piece, PieceColour, PieceType},
-    position::{Move, MoveResult, Position},
+    position::{
+        column_from_char, column_to_char, CoordinateMove, Move, MoveResult, NotationError, Position,
+    },
 };
 
 pub mod board_columns {
@@ -23,60 +25,96 @@ enum SquareStatus {
     Free,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum CastleDirection {
     KingSide,
     QueenSide,
 }
 
+#[derive(Clone)]
 pub struct Board {
     pieces_in_play: HashMap<Position, Piece>,
     move_to_draw_counter: i32,
+    last_move: Option<CoordinateMove>,
+    /// The pieces each side has taken and can drop back on the board, as (white, black), in
+    /// Crazyhouse. `None` when captured pieces leave the game.
+    pockets: Option<Pockets>,
+    /// Whether captures explode, as in atomic chess.
+    explosions: bool,
+}
+
+/// What a move took off the board, so that it can be put back.
+struct Removed {
+    /// The piece taken, if any.
+    captured: Option<Piece>,
+    /// In atomic chess, the capturing piece followed by every piece the explosion took with it.
+    exploded: Vec<Piece>,
 }
 
 const DRAW_MOVE_LIMIT: i32 = 50 * 2;
+/// The pieces in the White and Black pockets.
+type Pockets = (Vec<PieceType>, Vec<PieceType>);
+/// The order pieces are listed in a pocket.
+const POCKET_ORDER: [PieceType; 5] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+/// The pieces each side starts with, apart from the king.
+const STARTING_SET: [(PieceType, usize); 5] = [
+    (PieceType::Queen, 1),
+    (PieceType::Rook, 2),
+    (PieceType::Bishop, 2),
+    (PieceType::Knight, 2),
+    (PieceType::Pawn, 8),
+];
+
+impl Default for Board {
+    fn default() -> Self {
+        Board::new()
+    }
+}
+
+/// The first rank of the standard starting position, from the a-file to the h-file.
+pub const STANDARD_BACK_RANK: [PieceType; 8] = [
+    PieceType::Rook,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Queen,
+    PieceType::King,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Rook,
+];
 
 impl Board {
     pub fn new() -> Self {
-        let back_types: [PieceType; 8] = [
-            PieceType::Rook,
-            PieceType::Knight,
-            PieceType::Bishop,
-            PieceType::Queen,
-            PieceType::King,
-            PieceType::Bishop,
-            PieceType::Knight,
-            PieceType::Rook,
-        ];
-
-        let mut pieces_in_play: HashMap<Position, Piece> = HashMap::new();
-
-        for (i, &piece_type) in back_types.iter().enumerate() {
-            let special: bool = match piece_type {
-                PieceType::King | PieceType::Rook => true,
-                _ => false,
-            };
-
-            let new_white_piece: Piece = Piece::new(
-                piece_type,
-                board_columns::A + i,
-                1,
-                PieceColour::White,
-                special,
-            );
+        Board::with_back_rank(STANDARD_BACK_RANK)
+    }
 
-            let new_black_piece: Piece = Piece::new(
-                piece_type,
-                board_columns::A + i,
-                8,
-                PieceColour::Black,
-                special,
-            );
+    /// The starting position with `back_types` on both first ranks, from the a-file to the
+    /// h-file, such as a Chess960 arrangement. Both sides may castle with either rook.
+    pub fn with_back_rank(back_types: [PieceType; 8]) -> Self {
+        Board::with_back_ranks(back_types, back_types)
+    }
 
-            let new_white_position: Position = Position::new(1, board_columns::A + i);
-            let new_black_position: Position = Position::new(8, board_columns::A + i);
+    /// The starting position with a different first rank for each side, as in Double Fischer
+    /// Random. The ranks are not checked; `chess960::validate_back_rank` does that.
+    pub fn with_back_ranks(white_types: [PieceType; 8], black_types: [PieceType; 8]) -> Self {
+        let mut pieces_in_play: HashMap<Position, Piece> = HashMap::new();
 
-            pieces_in_play.insert(new_white_position, new_white_piece);
-            pieces_in_play.insert(new_black_position, new_black_piece);
+        for (colour, row, back_types) in [
+            (PieceColour::White, 1, white_types),
+            (PieceColour::Black, 8, black_types),
+        ] {
+            for (i, &piece_type) in back_types.iter().enumerate() {
+                let special: bool = matches!(piece_type, PieceType::King | PieceType::Rook);
+                let new_piece: Piece =
+                    Piece::new(piece_type, board_columns::A + i, row, colour, special);
+                pieces_in_play.insert(Position::new(row, board_columns::A + i), new_piece);
+            }
         }
 
         for column in board_columns::A..=board_columns::H {
@@ -95,51 +133,155 @@ impl Board {
         Board {
             pieces_in_play,
             move_to_draw_counter: 0,
+            last_move: None,
+            pockets: None,
+            explosions: false,
         }
     }
 
-    pub fn print(&self) {
-        let mut ascii_graphic: String = String::new();
-        let letters: [char; 8] = ['a', 'b', 'c', 'd', 'e', 'f', 'g', 'h'];
+    pub fn piece_at(&self, position: Position) -> Option<&Piece> {
+        self.pieces_in_play.get(&position)
+    }
 
-        ascii_graphic.push_str("  ");
-        for _ in 1..=16 {
-            ascii_graphic.push('_');
+    /// Keeps the pieces each side takes in its pocket, to be dropped back on the board as its
+    /// own, as in Crazyhouse.
+    pub fn use_pockets(&mut self) {
+        self.pockets.get_or_insert_with(Default::default);
+    }
+
+    pub fn has_pockets(&self) -> bool {
+        self.pockets.is_some()
+    }
+
+    /// The pieces `colour` can drop, most valuable first; empty without pockets.
+    pub fn pocket(&self, colour: PieceColour) -> &[PieceType] {
+        match (&self.pockets, colour) {
+            (Some((white, _)), PieceColour::White) => white,
+            (Some((_, black)), PieceColour::Black) => black,
+            (None, _) => &[],
         }
-        ascii_graphic.push('\n');
+    }
 
-        for row in (1..=8).rev() {
-            ascii_graphic.push_str(&row.to_string());
-            ascii_graphic.push('|');
-
-            for column in 1..=8 {
-                if let Some(p) = self.pieces_in_play.get(&Position::new(row, column)) {
-                    ascii_graphic.push_str(&format!("{} ", p.symbol));
-                } else if (row % 2) + (column % 2) == 1 {
-                    ascii_graphic.push_str("  ");
-                } else {
-                    ascii_graphic.push_str("██");
-                }
+    /// Makes every capture blow up the capturing piece along with all pieces but pawns next to
+    /// the capture square, as in atomic chess. Kings then cannot capture, and kings standing
+    /// side by side do not give check.
+    pub fn use_explosions(&mut self) {
+        self.explosions = true;
+    }
+
+    pub fn has_explosions(&self) -> bool {
+        self.explosions
+    }
+
+    /// Whether `colour` still has its king, which an explosion can take.
+    pub fn has_king(&self, colour: PieceColour) -> bool {
+        self.pieces_in_play
+            .values()
+            .any(|piece| piece.colour == colour && piece.piece_type == PieceType::King)
+    }
+
+    /// Puts `piece_type` in `colour`'s pocket, if the board has pockets.
+    fn add_to_pocket(&mut self, colour: PieceColour, piece_type: PieceType) {
+        let pocket: &mut Vec<PieceType> = match (&mut self.pockets, colour) {
+            (Some((white, _)), PieceColour::White) => white,
+            (Some((_, black)), PieceColour::Black) => black,
+            (None, _) => return,
+        };
+        pocket.push(piece_type);
+        pocket.sort_by_key(|piece_type| POCKET_ORDER.iter().position(|p| p == piece_type));
+    }
+
+    fn take_from_pocket(&mut self, colour: PieceColour, piece_type: PieceType) {
+        if let Some((white, black)) = &mut self.pockets {
+            let pocket: &mut Vec<PieceType> = match colour {
+                PieceColour::White => white,
+                PieceColour::Black => black,
+            };
+            if let Some(index) = pocket.iter().position(|&p| p == piece_type) {
+                pocket.remove(index);
             }
+        }
+    }
 
-            ascii_graphic.push('|');
-            ascii_graphic.push('\n');
+    /// Whether `colour` may drop `piece_type` on `square`: the piece is in its pocket, the
+    /// square is empty and, for a pawn, not on the first or last rank, and the drop does not
+    /// leave its king in check.
+    fn drop_can_be_played(
+        &mut self,
+        colour: PieceColour,
+        piece_type: PieceType,
+        square: Position,
+    ) -> bool {
+        if !self.pocket(colour).contains(&piece_type)
+            || self.validate_square(square, colour) != SquareStatus::Free
+            || (piece_type == PieceType::Pawn && (square.row == 1 || square.row == 8))
+        {
+            return false;
         }
-        ascii_graphic.push_str("  ");
+        let piece: Piece = Piece::new(piece_type, square.column, square.row, colour, false);
+        self.pieces_in_play.insert(square, piece);
+        let valid: bool = !self.is_in_check(colour);
+        self.pieces_in_play.remove(&square);
+        valid
+    }
 
-        for _ in 1..=16 {
-            ascii_graphic.push('‾');
+    /// Every drop `colour` can legally play.
+    fn legal_drops(&mut self, colour: PieceColour) -> Vec<CoordinateMove> {
+        let mut piece_types: Vec<PieceType> = self.pocket(colour).to_vec();
+        piece_types.dedup();
+        let mut drops: Vec<CoordinateMove> = Vec::new();
+        for piece_type in piece_types {
+            for row in 1..=8 {
+                for column in board_columns::A..=board_columns::H {
+                    let square: Position = Position::new(row, column);
+                    if self.drop_can_be_played(colour, piece_type, square) {
+                        drops.push(CoordinateMove::new_drop(piece_type, square));
+                    }
+                }
+            }
+        }
+        drops
+    }
+
+    /// Drops `piece_type` from `colour`'s pocket onto `square`, in Crazyhouse.
+    pub fn drop_piece(
+        &mut self,
+        colour: PieceColour,
+        piece_type: PieceType,
+        square: Position,
+    ) -> MoveResult {
+        if !self.pocket(colour).contains(&piece_type) {
+            return MoveResult::MissingPiece;
+        }
+        if !self.drop_can_be_played(colour, piece_type, square) {
+            return if self.is_in_check(colour) {
+                MoveResult::Checked
+            } else {
+                MoveResult::ImpossibleMove
+            };
         }
-        ascii_graphic.push('\n');
-        ascii_graphic.push_str("  ");
 
-        for letter in letters {
-            ascii_graphic.push(letter);
-            ascii_graphic.push(' ');
+        self.take_from_pocket(colour, piece_type);
+        let piece: Piece = Piece::new(piece_type, square.column, square.row, colour, false);
+        self.pieces_in_play.insert(square, piece);
+        self.last_move = Some(CoordinateMove::new_drop(piece_type, square));
+
+        for piece in self.pieces_in_play.values_mut() {
+            if piece.piece_type == PieceType::Pawn {
+                piece.special = false;
+            }
+        }
+        if piece_type == PieceType::Pawn {
+            self.move_to_draw_counter = 0;
+        } else {
+            self.move_to_draw_counter += 1;
         }
-        ascii_graphic.push('\n');
 
-        println!("\n{}", ascii_graphic);
+        if self.move_to_draw_counter == DRAW_MOVE_LIMIT {
+            MoveResult::Draw
+        } else {
+            MoveResult::Success
+        }
     }
 
     fn move_was_en_passant(
@@ -148,7 +290,7 @@ impl Board {
         old_position: Position,
         removed_piece: &Option<Piece>,
     ) -> bool {
-        if let Some(_) = removed_piece {
+        if removed_piece.is_some() {
             return false;
         }
         if self.pieces_in_play.get(&new_position).unwrap().piece_type != PieceType::Pawn {
@@ -167,12 +309,15 @@ impl Board {
         old_position: Position,
         colour: PieceColour,
     ) -> bool {
+        // the piece is gone if it exploded
+        let is_pawn: bool = self
+            .pieces_in_play
+            .get(&new_position)
+            .is_some_and(|piece| piece.piece_type == PieceType::Pawn);
         if colour == PieceColour::White {
-            new_position.row == old_position.row + 2
-                && self.pieces_in_play.get(&new_position).unwrap().piece_type == PieceType::Pawn
+            new_position.row == old_position.row + 2 && is_pawn
         } else {
-            new_position.row == old_position.row - 2
-                && self.pieces_in_play.get(&new_position).unwrap().piece_type == PieceType::Pawn
+            new_position.row + 2 == old_position.row && is_pawn
         }
     }
 
@@ -183,7 +328,7 @@ impl Board {
     ) -> Vec<&'a Piece> {
         let legal_pieces: Vec<&Piece> = pieces
             .into_iter()
-            .filter(|p| self.find_moves(&p).contains(&movement.new_position))
+            .filter(|p| self.find_moves(p).contains(&movement.new_position))
             .collect();
 
         legal_pieces
@@ -208,84 +353,144 @@ impl Board {
         pieces
     }
 
-    fn undo_move(
-        &mut self,
-        new_position: Position,
-        old_position: Position,
-        removed_piece: Option<Piece>,
-    ) {
+    fn undo_move(&mut self, new_position: Position, old_position: Position, removed: Removed) {
+        // the exploded pieces go back first, which puts the capturing piece back on the
+        // capture square
+        for piece in removed.exploded {
+            self.pieces_in_play.insert(piece.position, piece);
+        }
         let mut piece_to_move: Piece = self.pieces_in_play.remove(&new_position).unwrap();
         piece_to_move.position = old_position;
         self.pieces_in_play.insert(old_position, piece_to_move);
-        if let Some(removed_piece) = removed_piece {
+        if let Some(removed_piece) = removed.captured {
             self.pieces_in_play
                 .insert(removed_piece.position, removed_piece);
         }
     }
 
+    /// The rook `colour` would castle with towards `direction`: the outermost rook that has
+    /// not moved on that side of the king, as long as the king has not moved either.
+    fn castling_rook(&self, colour: PieceColour, direction: &CastleDirection) -> Option<Position> {
+        let king_position: Position = self.find_king_position(colour);
+        if king_position.row != home_row(colour) || !self.pieces_in_play[&king_position].special {
+            return None;
+        }
+        let columns: Vec<usize> = match direction {
+            CastleDirection::KingSide => (king_position.column + 1..=board_columns::H)
+                .rev()
+                .collect(),
+            CastleDirection::QueenSide => (board_columns::A..king_position.column).collect(),
+        };
+        columns
+            .into_iter()
+            .map(|column| Position::new(king_position.row, column))
+            .find(|position| {
+                self.pieces_in_play.get(position).is_some_and(|piece| {
+                    piece.piece_type == PieceType::Rook && piece.colour == colour && piece.special
+                })
+            })
+    }
+
+    /// Castling follows the Chess960 rules, which include the standard ones: the king and rook
+    /// have not moved, every square either of them crosses or lands on is empty apart from the
+    /// two of them, and the king is not in check on any square from where it starts to where
+    /// it ends up.
     fn is_castle_available(&self, direction: &CastleDirection, colour: PieceColour) -> bool {
+        let Some(rook_position) = self.castling_rook(colour, direction) else {
+            return false;
+        };
         if self.is_in_check(colour) {
             return false;
         }
 
-        // king is good
         let king_position: Position = self.find_king_position(colour);
-        if let None = self.pieces_in_play.get(&king_position) {
-            panic!("{:?}", king_position);
-        }
-        if self.pieces_in_play.get(&king_position).unwrap().special == false {
+        let (king_target, rook_target): (Position, Position) =
+            castled_positions(king_position.row, direction);
+        let between = |from: Position, to: Position| {
+            (from.column.min(to.column)..=from.column.max(to.column))
+                .map(move |column| Position::new(from.row, column))
+        };
+        let crossed: Vec<Position> = between(king_position, king_target)
+            .chain(between(rook_position, rook_target))
+            .collect();
+        if crossed.iter().any(|square| {
+            *square != king_position
+                && *square != rook_position
+                && self.pieces_in_play.contains_key(square)
+        }) {
             return false;
         }
 
-        let rook_position: Position = match (&direction, colour) {
-            (CastleDirection::KingSide, PieceColour::White) => Position::new(1, board_columns::H),
-            (CastleDirection::KingSide, PieceColour::Black) => Position::new(8, board_columns::H),
-            (CastleDirection::QueenSide, PieceColour::White) => Position::new(1, board_columns::A),
-            (CastleDirection::QueenSide, PieceColour::Black) => Position::new(8, board_columns::A),
-        };
-        if let Some(piece) = self.pieces_in_play.get(&rook_position) {
-            if piece.piece_type != PieceType::Rook {
+        // try the king on every square of its way with the castling rook off the board, since
+        // in Chess960 the king can cross the rook's square
+        let mut board: Board = self.clone();
+        let mut king: Piece = board.pieces_in_play.remove(&king_position).unwrap();
+        board.pieces_in_play.remove(&rook_position);
+        for square in between(king_position, king_target) {
+            king.position = square;
+            board.pieces_in_play.insert(square, king.clone());
+            let attacked: bool = board.is_in_check(colour);
+            board.pieces_in_play.remove(&square);
+            if attacked {
                 return false;
             }
-            if piece.special == false {
-                return false;
-            }
-        } else {
-            return false;
         }
 
-        let row: usize = match colour {
-            PieceColour::White => 1,
-            PieceColour::Black => 8,
-        };
-
-        let first_column: usize = match &direction {
-            CastleDirection::KingSide => king_position.column + 1,
-            CastleDirection::QueenSide => board_columns::C,
-        };
+        let mut castled: Board = self.clone();
+        castled.place_castled(colour, direction);
+        !castled.is_in_check(colour)
+    }
 
-        let last_column: usize = match &direction {
-            CastleDirection::KingSide => board_columns::H,
-            CastleDirection::QueenSide => king_position.column,
-        };
+    /// Moves the king and the castling rook to their squares after castling.
+    fn place_castled(&mut self, colour: PieceColour, direction: &CastleDirection) {
+        let king_position: Position = self.find_king_position(colour);
+        let rook_position: Position = self.castling_rook(colour, direction).unwrap();
+        let (king_target, rook_target): (Position, Position) =
+            castled_positions(king_position.row, direction);
+        let mut rook: Piece = self.pieces_in_play.remove(&rook_position).unwrap();
+        let mut king: Piece = self.pieces_in_play.remove(&king_position).unwrap();
+        king.special = false;
+        rook.special = false;
+        king.position = king_target;
+        rook.position = rook_target;
+        self.pieces_in_play.insert(king_target, king);
+        self.pieces_in_play.insert(rook_target, rook);
+    }
 
-        let enemy_colour: PieceColour = match colour {
-            PieceColour::Black => PieceColour::White,
-            PieceColour::White => PieceColour::Black,
+    /// How a castling move is written in coordinates: the king moving to its square when that
+    /// is two or more columns away, as in standard chess ("e1g1"), and otherwise the king
+    /// taking its own rook ("b1a1"), as Chess960 programs do.
+    fn castling_move(&self, colour: PieceColour, direction: &CastleDirection) -> CoordinateMove {
+        let king_position: Position = self.find_king_position(colour);
+        let (king_target, _): (Position, Position) =
+            castled_positions(king_position.row, direction);
+        let to: Position = if king_position.column.abs_diff(king_target.column) >= 2 {
+            king_target
+        } else {
+            self.castling_rook(colour, direction).unwrap()
         };
+        CoordinateMove::new(king_position, to, None)
+    }
 
-        let moves: Vec<Position> = self.find_all_moves(enemy_colour);
-
-        for column in first_column + 1..last_column {
-            if let Some(_) = self.pieces_in_play.get(&Position::new(row, column)) {
-                return false;
-            }
-
-            if moves.contains(&Position::new(row, column)) {
-                return false;
-            }
+    /// The side `coordinate_move` castles to, if it is written as castling: a king moving onto
+    /// its own rook, or two or more columns onto the square castling takes it to.
+    fn castling_direction(&self, coordinate_move: &CoordinateMove) -> Option<CastleDirection> {
+        let (from, to): (Position, Position) = (coordinate_move.from, coordinate_move.to);
+        let king: &Piece = self.pieces_in_play.get(&from)?;
+        if king.piece_type != PieceType::King || from.row != to.row || from == to {
+            return None;
         }
-        true
+        let direction: CastleDirection = if to.column > from.column {
+            CastleDirection::KingSide
+        } else {
+            CastleDirection::QueenSide
+        };
+        let onto_own_rook: bool = self.pieces_in_play.get(&to).is_some_and(|piece| {
+            piece.piece_type == PieceType::Rook && piece.colour == king.colour
+        });
+        let onto_castled_square: bool =
+            from.column.abs_diff(to.column) >= 2 && to == castled_positions(from.row, &direction).0;
+        (onto_own_rook || onto_castled_square).then_some(direction)
     }
 
     fn has_valid_move(&mut self, player: PieceColour) -> bool {
@@ -307,6 +512,11 @@ impl Board {
             return true;
         }
 
+        // a piece dropped in between can stop a check
+        if !self.legal_drops(player).is_empty() {
+            return true;
+        }
+
         if moves_to_check.is_empty() {
             return false;
         }
@@ -345,20 +555,16 @@ impl Board {
     }
 
     pub fn is_promotion_available(&self, colour: PieceColour, movement: &Move) -> bool {
-        if self
-            .pieces_in_play
-            .get(&movement.new_position)
-            .unwrap()
-            .piece_type
-            != PieceType::Pawn
-        {
+        // a pawn that captured in atomic chess is gone
+        if !matches!(
+            self.pieces_in_play.get(&movement.new_position),
+            Some(piece) if piece.piece_type == PieceType::Pawn
+        ) {
             false
         } else if colour == PieceColour::White && movement.new_position.row == 8 {
             true
-        } else if colour == PieceColour::Black && movement.new_position.row == 1 {
-            true
         } else {
-            false
+            colour == PieceColour::Black && movement.new_position.row == 1
         }
     }
 
@@ -371,15 +577,28 @@ impl Board {
         let mut valid: bool = true;
         let mut piece_to_move: Piece = self.pieces_in_play.remove(&old_position).unwrap();
         piece_to_move.position = new_position;
-        let removed_piece: Option<Piece> =
-            self.handle_capture(new_position, old_position, piece_to_move);
-        if self.is_in_check(colour) {
+        let removed: Removed = self.handle_capture(new_position, old_position, piece_to_move);
+        if !self.is_king_safe(colour) {
             valid = false;
         }
-        self.undo_move(new_position, old_position, removed_piece);
+        self.undo_move(new_position, old_position, removed);
         valid
     }
 
+    /// Whether `colour`'s king is safe after a move it has just tried: not in check or, in
+    /// atomic chess, still standing with the enemy king blown up.
+    fn is_king_safe(&self, colour: PieceColour) -> bool {
+        if self.explosions {
+            if !self.has_king(colour) {
+                return false;
+            }
+            if !self.has_king(colour.opponent()) {
+                return true;
+            }
+        }
+        !self.is_in_check(colour)
+    }
+
     fn find_king_position(&self, colour: PieceColour) -> Position {
         let mut king_position: Position = Position::new(0, 0);
         for piece in self.pieces_in_play.values() {
@@ -391,12 +610,28 @@ impl Board {
         king_position
     }
 
+    /// Where `colour`'s king stands, if it is in check.
+    pub fn checked_king(&self, colour: PieceColour) -> Option<Position> {
+        if self.is_in_check(colour) {
+            Some(self.find_king_position(colour))
+        } else {
+            None
+        }
+    }
+
     fn is_in_check(&self, player: PieceColour) -> bool {
         let king_position: Position = self.find_king_position(player);
         let enemy_colour: PieceColour = match player {
             PieceColour::Black => PieceColour::White,
             PieceColour::White => PieceColour::Black,
         };
+        // kings side by side cannot take each other without blowing up their own
+        if self.explosions
+            && self.has_king(enemy_colour)
+            && are_adjacent(king_position, self.find_king_position(enemy_colour))
+        {
+            return false;
+        }
         let enemy_moves: Vec<Position> = self.find_all_moves(enemy_colour);
 
         enemy_moves.contains(&king_position)
@@ -416,13 +651,13 @@ impl Board {
     pub fn make_move(&mut self, colour: PieceColour, movement: &Move) -> MoveResult {
         let started_in_check: bool = self.is_in_check(colour);
 
-        let pieces: Vec<&Piece> = self.possible_pieces_for_move(colour, &movement);
+        let pieces: Vec<&Piece> = self.possible_pieces_for_move(colour, movement);
 
         if pieces.is_empty() {
             return MoveResult::MissingPiece;
         }
 
-        let pieces = self.pieces_with_legal_moves(pieces, &movement);
+        let pieces = self.pieces_with_legal_moves(pieces, movement);
 
         let piece_to_move: &Piece = if pieces.len() == 1 {
             pieces.first().unwrap()
@@ -438,19 +673,38 @@ impl Board {
         // update its position
         piece_to_move.position = movement.new_position;
         // get the captured piece if any and puts piece on teh new position
-        let removed_piece: Option<Piece> =
+        let removed: Removed =
             self.handle_capture(movement.new_position, old_position, piece_to_move);
 
         // if in check after move undo the move
-        if self.is_in_check(colour) {
-            self.undo_move(movement.new_position, old_position, removed_piece);
-            if started_in_check || movement.piece_type == PieceType::King {
+        if !self.is_king_safe(colour) {
+            let king_exploded: bool = !self.has_king(colour);
+            self.undo_move(movement.new_position, old_position, removed);
+            if king_exploded {
+                return MoveResult::ImpossibleMove;
+            } else if started_in_check || movement.piece_type == PieceType::King {
                 return MoveResult::Checked;
             } else {
                 return MoveResult::PiecePinned;
             }
         }
 
+        self.last_move = Some(CoordinateMove::new(
+            old_position,
+            movement.new_position,
+            None,
+        ));
+
+        // a promoted piece goes back to being a pawn in the pocket
+        if let Some(captured) = &removed.captured {
+            let piece_type: PieceType = if captured.promoted {
+                PieceType::Pawn
+            } else {
+                captured.piece_type
+            };
+            self.add_to_pocket(colour, piece_type);
+        }
+
         // en passant no longer available for any pawn that has jumped last move
         for piece in self.pieces_in_play.values_mut() {
             if piece.piece_type == PieceType::Pawn {
@@ -459,23 +713,14 @@ impl Board {
         }
 
         // en passant available on the pawn that jumped this move
-        if self.move_was_pawn_jump(movement.new_position, old_position, colour) {
-            self.pieces_in_play
-                .get_mut(&movement.new_position)
-                .unwrap()
-                .special = true;
-        } else {
-            // if piece was not a pawn that jumped turn off special in case it was a king or rook so it can no longer castle
-            self.pieces_in_play
-                .get_mut(&movement.new_position)
-                .unwrap()
-                .special = false;
+        let jumped: bool = self.move_was_pawn_jump(movement.new_position, old_position, colour);
+        // if piece was not a pawn that jumped turn off special in case it was a king or rook so it can no longer castle
+        if let Some(moved_piece) = self.pieces_in_play.get_mut(&movement.new_position) {
+            moved_piece.special = jumped;
         }
 
         // 50 move rule shenanigans
-        if let Some(_) = removed_piece {
-            self.move_to_draw_counter = 0;
-        } else if movement.piece_type == PieceType::Pawn {
+        if removed.captured.is_some() || movement.piece_type == PieceType::Pawn {
             self.move_to_draw_counter = 0;
         } else {
             self.move_to_draw_counter += 1;
@@ -495,38 +740,593 @@ impl Board {
             return MoveResult::ImpossibleMove;
         }
 
-        let king_position: Position = self.find_king_position(colour);
-        let rook_position: Position = match (&direction, colour) {
-            (CastleDirection::KingSide, PieceColour::White) => Position::new(1, board_columns::H),
-            (CastleDirection::KingSide, PieceColour::Black) => Position::new(8, board_columns::H),
-            (CastleDirection::QueenSide, PieceColour::White) => Position::new(1, board_columns::A),
-            (CastleDirection::QueenSide, PieceColour::Black) => Position::new(8, board_columns::A),
+        let castling_move: CoordinateMove = self.castling_move(colour, &direction);
+        self.place_castled(colour, &direction);
+        self.last_move = Some(castling_move);
+
+        // castling is a quiet move: it also ends any en passant opportunity
+        for piece in self.pieces_in_play.values_mut() {
+            if piece.piece_type == PieceType::Pawn {
+                piece.special = false;
+            }
+        }
+        self.move_to_draw_counter += 1;
+
+        if self.move_to_draw_counter == DRAW_MOVE_LIMIT {
+            MoveResult::Draw
+        } else {
+            MoveResult::Success
+        }
+    }
+
+    /// Plays a move typed as "O-O"/"O-O-O", in coordinate notation ("e2e4") or in the short
+    /// algebraic form understood by `Move::from_notation`.
+    pub fn play_notation(
+        &mut self,
+        colour: PieceColour,
+        notation: &str,
+    ) -> Result<MoveResult, NotationError> {
+        if notation.eq_ignore_ascii_case("o-o") {
+            Ok(self.castle(colour, CastleDirection::KingSide))
+        } else if notation.eq_ignore_ascii_case("o-o-o") {
+            Ok(self.castle(colour, CastleDirection::QueenSide))
+        } else if let Ok(coordinate_move) = CoordinateMove::from_notation(notation) {
+            Ok(self.play_coordinate_move(colour, &coordinate_move))
+        } else {
+            let movement: Move = Move::from_notation(notation)?;
+            Ok(self.make_move(colour, &movement))
+        }
+    }
+
+    /// Plays a move given by its origin and destination squares, or a drop.
+    ///
+    /// A king moving onto its own rook, or two or more columns to the square castling takes
+    /// it to, castles. If the move reaches the last rank and `promotion` is set the pawn is
+    /// promoted straight away.
+    pub fn play_coordinate_move(
+        &mut self,
+        colour: PieceColour,
+        coordinate_move: &CoordinateMove,
+    ) -> MoveResult {
+        if let Some(piece_type) = coordinate_move.drop {
+            return self.drop_piece(colour, piece_type, coordinate_move.to);
+        }
+
+        let piece_type: PieceType = match self.pieces_in_play.get(&coordinate_move.from) {
+            Some(piece) if piece.colour == colour => piece.piece_type,
+            _ => return MoveResult::MissingPiece,
+        };
+
+        if let Some(direction) = self.castling_direction(coordinate_move) {
+            return self.castle(colour, direction);
+        }
+
+        let movement: Move = Move::new(
+            coordinate_move.to,
+            Some(coordinate_move.from.column),
+            Some(coordinate_move.from.row),
+            piece_type,
+        );
+
+        match (self.make_move(colour, &movement), coordinate_move.promotion) {
+            (MoveResult::PromotionAvailable(square), Some(promotion)) => {
+                self.promote(square, colour, promotion);
+                MoveResult::Success
+            }
+            (result, _) => result,
+        }
+    }
+
+    /// The squares the piece on `from` can legally move to.
+    pub fn destinations(&self, colour: PieceColour, from: Position) -> Vec<Position> {
+        let mut destinations: Vec<Position> = self
+            .clone()
+            .legal_moves(colour)
+            .into_iter()
+            .filter(|coordinate_move| {
+                coordinate_move.from == from && coordinate_move.drop.is_none()
+            })
+            .map(|coordinate_move| coordinate_move.to)
+            .collect();
+        // promotions list the same destination once per piece
+        destinations.dedup();
+        destinations
+    }
+
+    /// Every move `colour` can legally play, with promotions listed once per piece type, and
+    /// drops when the board has pockets.
+    pub fn legal_moves(&mut self, colour: PieceColour) -> Vec<CoordinateMove> {
+        let mut candidates: Vec<(Position, Position, PieceType)> = Vec::new();
+        for piece in self.pieces_in_play.values() {
+            if piece.colour != colour {
+                continue;
+            }
+            for new_position in self.find_moves(piece) {
+                candidates.push((piece.position, new_position, piece.piece_type));
+            }
+        }
+
+        let last_row: usize = match colour {
+            PieceColour::White => 8,
+            PieceColour::Black => 1,
+        };
+
+        let mut moves: Vec<CoordinateMove> = Vec::new();
+        for (old_position, new_position, piece_type) in candidates {
+            if !self.move_can_be_played(old_position, new_position, colour) {
+                continue;
+            }
+            if piece_type == PieceType::Pawn && new_position.row == last_row {
+                for promotion in [
+                    PieceType::Queen,
+                    PieceType::Rook,
+                    PieceType::Bishop,
+                    PieceType::Knight,
+                ] {
+                    moves.push(CoordinateMove::new(
+                        old_position,
+                        new_position,
+                        Some(promotion),
+                    ));
+                }
+            } else {
+                moves.push(CoordinateMove::new(old_position, new_position, None));
+            }
+        }
+
+        for direction in [CastleDirection::KingSide, CastleDirection::QueenSide] {
+            if self.is_castle_available(&direction, colour) {
+                moves.push(self.castling_move(colour, &direction));
+            }
+        }
+
+        moves.extend(self.legal_drops(colour));
+        moves
+    }
+
+    /// Whether neither side can checkmate any more: only kings, a single knight or bishop beside
+    /// them, or any number of bishops all standing on squares of one colour. Never with pockets,
+    /// where every capture can come back.
+    pub fn is_insufficient_material(&self) -> bool {
+        if self.has_pockets() {
+            return false;
+        }
+        let mut minor_pieces: Vec<&Piece> = Vec::new();
+        for piece in self.pieces_in_play.values() {
+            match piece.piece_type {
+                PieceType::King => (),
+                PieceType::Bishop | PieceType::Knight => minor_pieces.push(piece),
+                _ => return false,
+            }
+        }
+        if minor_pieces.len() <= 1 {
+            return true;
+        }
+        let square_colour = |piece: &&Piece| (piece.position.row + piece.position.column) % 2;
+        minor_pieces
+            .iter()
+            .all(|piece| piece.piece_type == PieceType::Bishop)
+            && minor_pieces
+                .iter()
+                .all(|piece| square_colour(piece) == square_colour(&minor_pieces[0]))
+    }
+
+    /// Sum of the values of the pieces `colour` has left on the board.
+    pub fn material(&self, colour: PieceColour) -> i32 {
+        self.pieces_in_play
+            .values()
+            .filter(|p| p.colour == colour)
+            .map(|p| p.piece_type.value())
+            .sum()
+    }
+
+    /// The pieces `colour` has lost, most valuable first, found by comparing what is left with
+    /// the starting set. Pieces beyond the starting number of their type can only have come
+    /// from promotion, so each of them stands for a pawn that was not captured.
+    pub fn captured(&self, colour: PieceColour) -> Vec<PieceType> {
+        let count = |piece_type: PieceType| -> usize {
+            self.pieces_in_play
+                .values()
+                .filter(|p| p.colour == colour && p.piece_type == piece_type)
+                .count()
         };
+        let mut captured: Vec<PieceType> = Vec::new();
+        let mut promoted: usize = 0;
+        for (piece_type, starting) in STARTING_SET {
+            let left: usize = if piece_type == PieceType::Pawn {
+                count(piece_type) + promoted
+            } else {
+                count(piece_type)
+            };
+            promoted += left.saturating_sub(starting);
+            for _ in left..starting {
+                captured.push(piece_type);
+            }
+        }
+        captured
+    }
+
+    /// Builds a board from Forsyth-Edwards Notation, returning it with the side to move.
+    pub fn from_fen(fen: &str) -> Result<(Board, PieceColour), NotationError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if fields.len() < 4 {
+            return Err(NotationError);
+        }
 
-        let king_new_position: Position = match &direction {
-            CastleDirection::KingSide => Position::new(king_position.row, king_position.column + 2),
-            CastleDirection::QueenSide => {
-                Position::new(king_position.row, king_position.column - 2)
+        let mut pieces_in_play: HashMap<Position, Piece> = HashMap::new();
+        // Crazyhouse pockets follow the placement in brackets, as in ".../RNBQKBNR[Qp]"
+        let (placement, pockets): (&str, Option<Pockets>) = match fields[0].split_once('[') {
+            Some((placement, pocket)) => {
+                let pocket: &str = pocket.strip_suffix(']').ok_or(NotationError)?;
+                let mut pockets: Pockets = Default::default();
+                for letter in pocket.chars() {
+                    let piece_type: PieceType =
+                        PieceType::from_letter(letter).ok_or(NotationError)?;
+                    match letter.is_ascii_uppercase() {
+                        true => pockets.0.push(piece_type),
+                        false => pockets.1.push(piece_type),
+                    }
+                }
+                for pocket in [&mut pockets.0, &mut pockets.1] {
+                    pocket.sort_by_key(|piece_type| {
+                        POCKET_ORDER.iter().position(|p| p == piece_type)
+                    });
+                }
+                (placement, Some(pockets))
             }
+            None => (fields[0], None),
         };
-        let rook_new_position: Position = match &direction {
-            CastleDirection::KingSide => {
-                Position::new(rook_position.row, king_new_position.column - 1)
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != 8 {
+            return Err(NotationError);
+        }
+
+        for (i, rank) in ranks.iter().enumerate() {
+            let row: usize = 8 - i;
+            let mut column: usize = board_columns::A;
+            for symbol in rank.chars() {
+                if let Some(empty) = symbol.to_digit(10) {
+                    column += empty as usize;
+                    continue;
+                }
+                // "~" marks the piece before it as a promoted pawn
+                if symbol == '~' {
+                    let promoted: &mut Piece = pieces_in_play
+                        .get_mut(&Position::new(row, column - 1))
+                        .ok_or(NotationError)?;
+                    promoted.promoted = true;
+                    continue;
+                }
+                let piece_type: PieceType = PieceType::from_letter(symbol).ok_or(NotationError)?;
+                let colour: PieceColour = if symbol.is_ascii_uppercase() {
+                    PieceColour::White
+                } else {
+                    PieceColour::Black
+                };
+                if column > board_columns::H {
+                    return Err(NotationError);
+                }
+                pieces_in_play.insert(
+                    Position::new(row, column),
+                    Piece::new(piece_type, column, row, colour, false),
+                );
+                column += 1;
             }
-            CastleDirection::QueenSide => {
-                Position::new(rook_position.row, king_new_position.column + 1)
+            if column != board_columns::H + 1 {
+                return Err(NotationError);
+            }
+        }
+
+        for colour in [PieceColour::White, PieceColour::Black] {
+            let kings: usize = pieces_in_play
+                .values()
+                .filter(|p| p.colour == colour && p.piece_type == PieceType::King)
+                .count();
+            if kings != 1 {
+                return Err(NotationError);
             }
+        }
+
+        let turn: PieceColour = match fields[1] {
+            "w" => PieceColour::White,
+            "b" => PieceColour::Black,
+            _ => return Err(NotationError),
         };
-        let mut rook: Piece = self.pieces_in_play.remove(&rook_position).unwrap();
-        let mut king: Piece = self.pieces_in_play.remove(&king_position).unwrap();
-        king.special = false;
-        rook.special = false;
-        king.position = king_new_position;
-        rook.position = rook_new_position;
-        self.pieces_in_play.insert(king_new_position, king);
-        self.pieces_in_play.insert(rook_new_position, rook);
 
-        MoveResult::Success
+        // X-FEN: K and Q give the outermost rook on that side of the king, and a file letter
+        // (as in Shredder-FEN) any other rook
+        if fields[2] != "-" {
+            for right in fields[2].chars() {
+                let colour: PieceColour = if right.is_ascii_uppercase() {
+                    PieceColour::White
+                } else {
+                    PieceColour::Black
+                };
+                let row: usize = home_row(colour);
+                let is_ours = |position: &Position, piece_type: PieceType| {
+                    pieces_in_play
+                        .get(position)
+                        .is_some_and(|p| p.piece_type == piece_type && p.colour == colour)
+                };
+                let Some(king_position) = (board_columns::A..=board_columns::H)
+                    .map(|column| Position::new(row, column))
+                    .find(|position| is_ours(position, PieceType::King))
+                else {
+                    continue;
+                };
+                let rook_position: Option<Position> = match right.to_ascii_lowercase() {
+                    'k' => (king_position.column + 1..=board_columns::H)
+                        .rev()
+                        .map(|column| Position::new(row, column))
+                        .find(|position| is_ours(position, PieceType::Rook)),
+                    'q' => (board_columns::A..king_position.column)
+                        .map(|column| Position::new(row, column))
+                        .find(|position| is_ours(position, PieceType::Rook)),
+                    letter => {
+                        let column: usize = column_from_char(letter).ok_or(NotationError)?;
+                        Some(Position::new(row, column))
+                            .filter(|position| is_ours(position, PieceType::Rook))
+                    }
+                };
+                if let Some(rook_position) = rook_position {
+                    pieces_in_play.get_mut(&king_position).unwrap().special = true;
+                    pieces_in_play.get_mut(&rook_position).unwrap().special = true;
+                }
+            }
+        }
+
+        if fields[3] != "-" {
+            let target: Position = Position::from_notation(fields[3])?;
+            let pawn_position: Position = match target.row {
+                3 => Position::new(4, target.column),
+                6 => Position::new(5, target.column),
+                _ => return Err(NotationError),
+            };
+            if let Some(pawn) = pieces_in_play.get_mut(&pawn_position) {
+                if pawn.piece_type == PieceType::Pawn {
+                    pawn.special = true;
+                }
+            }
+        }
+
+        let move_to_draw_counter: i32 = match fields.get(4) {
+            Some(halfmoves) => halfmoves.parse::<i32>().map_err(|_| NotationError)?,
+            None => 0,
+        };
+
+        Ok((
+            Board {
+                pieces_in_play,
+                move_to_draw_counter,
+                last_move: None,
+                pockets,
+                explosions: false,
+            },
+            turn,
+        ))
+    }
+
+    pub fn to_fen(&self, turn: PieceColour, move_number: usize) -> String {
+        let mut fen: String = String::new();
+
+        for row in (1..=8).rev() {
+            let mut empty: usize = 0;
+            for column in board_columns::A..=board_columns::H {
+                match self.pieces_in_play.get(&Position::new(row, column)) {
+                    Some(piece) => {
+                        if empty > 0 {
+                            fen.push_str(&empty.to_string());
+                            empty = 0;
+                        }
+                        let letter: char = piece.piece_type.letter();
+                        match piece.colour {
+                            PieceColour::White => fen.push(letter),
+                            PieceColour::Black => fen.push(letter.to_ascii_lowercase()),
+                        }
+                        if piece.promoted && self.has_pockets() {
+                            fen.push('~');
+                        }
+                    }
+                    None => empty += 1,
+                }
+            }
+            if empty > 0 {
+                fen.push_str(&empty.to_string());
+            }
+            if row > 1 {
+                fen.push('/');
+            }
+        }
+        if self.has_pockets() {
+            fen.push('[');
+            for colour in [PieceColour::White, PieceColour::Black] {
+                for piece_type in self.pocket(colour) {
+                    fen.push(match colour {
+                        PieceColour::White => piece_type.letter(),
+                        PieceColour::Black => piece_type.letter().to_ascii_lowercase(),
+                    });
+                }
+            }
+            fen.push(']');
+        }
+
+        fen.push_str(match turn {
+            PieceColour::White => " w ",
+            PieceColour::Black => " b ",
+        });
+
+        let mut castling: String = String::new();
+        for colour in [PieceColour::White, PieceColour::Black] {
+            for direction in [CastleDirection::KingSide, CastleDirection::QueenSide] {
+                if let Some(symbol) = self.castling_symbol(colour, &direction) {
+                    castling.push(symbol);
+                }
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        fen.push_str(&castling);
+
+        // only written when a pawn can actually take en passant, so repeated positions match
+        let en_passant: Option<Position> = self
+            .pieces_in_play
+            .values()
+            .find(|p| p.piece_type == PieceType::Pawn && p.special && p.colour != turn)
+            .filter(|p| {
+                [p.position.column - 1, p.position.column + 1]
+                    .iter()
+                    .any(|&column| {
+                        self.pieces_in_play
+                            .get(&Position::new(p.position.row, column))
+                            .is_some_and(|n| n.piece_type == PieceType::Pawn && n.colour == turn)
+                    })
+            })
+            .map(|p| match p.colour {
+                PieceColour::White => Position::new(p.position.row - 1, p.position.column),
+                PieceColour::Black => Position::new(p.position.row + 1, p.position.column),
+            });
+        match en_passant {
+            Some(target) => fen.push_str(&format!(" {} ", target)),
+            None => fen.push_str(" - "),
+        }
+
+        fen.push_str(&format!("{} {}", self.move_to_draw_counter, move_number));
+        fen
+    }
+
+    /// Whether either side may still castle in a way only Chess960 allows: with its king off
+    /// the e-file, or with a rook that did not start in a corner.
+    pub fn is_chess960(&self) -> bool {
+        [PieceColour::White, PieceColour::Black]
+            .into_iter()
+            .any(|colour| {
+                [
+                    (CastleDirection::KingSide, board_columns::H),
+                    (CastleDirection::QueenSide, board_columns::A),
+                ]
+                .into_iter()
+                .any(|(direction, corner)| {
+                    self.castling_rook(colour, &direction).is_some_and(|rook| {
+                        rook.column != corner
+                            || self.find_king_position(colour).column != board_columns::E
+                    })
+                })
+            })
+    }
+
+    /// The X-FEN letter of `colour`'s right to castle towards `direction`, if it has one: K or
+    /// Q when castling is with the outermost rook on that side, otherwise the rook's file.
+    fn castling_symbol(&self, colour: PieceColour, direction: &CastleDirection) -> Option<char> {
+        let rook_position: Position = self.castling_rook(colour, direction)?;
+        let outermost: bool = !self.pieces_in_play.values().any(|piece| {
+            piece.piece_type == PieceType::Rook
+                && piece.colour == colour
+                && piece.position.row == rook_position.row
+                && match direction {
+                    CastleDirection::KingSide => piece.position.column > rook_position.column,
+                    CastleDirection::QueenSide => piece.position.column < rook_position.column,
+                }
+        });
+        let symbol: char = match (outermost, direction) {
+            (true, CastleDirection::KingSide) => 'k',
+            (true, CastleDirection::QueenSide) => 'q',
+            (false, _) => column_to_char(rook_position.column),
+        };
+        Some(match colour {
+            PieceColour::White => symbol.to_ascii_uppercase(),
+            PieceColour::Black => symbol,
+        })
+    }
+
+    /// Identifies a position for repetition: the FEN without the move counters.
+    pub fn position_key(&self, turn: PieceColour) -> String {
+        let fen: String = self.to_fen(turn, 1);
+        fen.split_whitespace()
+            .take(4)
+            .collect::<Vec<&str>>()
+            .join(" ")
+    }
+
+    /// Standard Algebraic Notation for a legal move `colour` is about to play.
+    pub fn san(&self, colour: PieceColour, coordinate_move: &CoordinateMove) -> String {
+        let mut san: String = String::new();
+
+        if coordinate_move.drop.is_some() {
+            san.push_str(&coordinate_move.to_notation());
+        } else if let Some(direction) = self.castling_direction(coordinate_move) {
+            san.push_str(match direction {
+                CastleDirection::KingSide => "O-O",
+                CastleDirection::QueenSide => "O-O-O",
+            });
+        } else {
+            let piece: &Piece = self.pieces_in_play.get(&coordinate_move.from).unwrap();
+            let capture: bool = self.pieces_in_play.contains_key(&coordinate_move.to)
+                || (piece.piece_type == PieceType::Pawn
+                    && coordinate_move.from.column != coordinate_move.to.column);
+
+            if piece.piece_type == PieceType::Pawn {
+                if capture {
+                    san.push(column_to_char(coordinate_move.from.column));
+                }
+            } else {
+                san.push(piece.piece_type.letter());
+
+                let mut search_board: Board = self.clone();
+                let rivals: Vec<Position> = self
+                    .pieces_in_play
+                    .values()
+                    .filter(|p| {
+                        p.colour == colour
+                            && p.piece_type == piece.piece_type
+                            && p.position != coordinate_move.from
+                            && self.find_moves(p).contains(&coordinate_move.to)
+                    })
+                    .map(|p| p.position)
+                    .collect();
+                let rivals: Vec<Position> = rivals
+                    .into_iter()
+                    .filter(|&rival| {
+                        search_board.move_can_be_played(rival, coordinate_move.to, colour)
+                    })
+                    .collect();
+
+                if !rivals.is_empty() {
+                    if rivals
+                        .iter()
+                        .all(|rival| rival.column != coordinate_move.from.column)
+                    {
+                        san.push(column_to_char(coordinate_move.from.column));
+                    } else if rivals
+                        .iter()
+                        .all(|rival| rival.row != coordinate_move.from.row)
+                    {
+                        san.push_str(&coordinate_move.from.row.to_string());
+                    } else {
+                        san.push_str(&coordinate_move.from.to_string());
+                    }
+                }
+            }
+
+            if capture {
+                san.push('x');
+            }
+            san.push_str(&coordinate_move.to.to_string());
+            if let Some(promotion) = coordinate_move.promotion {
+                san.push('=');
+                san.push(promotion.letter());
+            }
+        }
+
+        let enemy_colour: PieceColour = colour.opponent();
+        let mut after_move: Board = self.clone();
+        after_move.play_coordinate_move(colour, coordinate_move);
+        if after_move.is_mate(enemy_colour) || !after_move.has_king(enemy_colour) {
+            san.push('#');
+        } else if after_move.is_in_check(enemy_colour) {
+            san.push('+');
+        }
+        san
     }
 
     /// Be sure to check it the moved piece was a pawn!
@@ -548,18 +1348,46 @@ impl Board {
         captured_pawn
     }
 
+    /// Puts `piece_to_move` on `new_position`, taking whatever it captures, and in atomic
+    /// chess sets off the explosion.
     fn handle_capture(
         &mut self,
         new_position: Position,
         old_position: Position,
         piece_to_move: Piece,
-    ) -> Option<Piece> {
+    ) -> Removed {
         let mut removed_piece: Option<Piece> =
             self.pieces_in_play.insert(new_position, piece_to_move);
         if self.move_was_en_passant(new_position, old_position, &removed_piece) {
             removed_piece = self.handle_en_passant(new_position, old_position);
         }
-        removed_piece
+        let exploded: Vec<Piece> = if self.explosions && removed_piece.is_some() {
+            self.explode(new_position)
+        } else {
+            Vec::new()
+        };
+        Removed {
+            captured: removed_piece,
+            exploded,
+        }
+    }
+
+    /// Takes the piece that captured on `square` off the board along with every piece but a
+    /// pawn next to it. Returns them all, the capturing piece first.
+    fn explode(&mut self, square: Position) -> Vec<Piece> {
+        let caught: Vec<Position> = self
+            .pieces_in_play
+            .values()
+            .filter(|piece| {
+                piece.piece_type != PieceType::Pawn && are_adjacent(piece.position, square)
+            })
+            .map(|piece| piece.position)
+            .collect();
+        let mut exploded: Vec<Piece> = vec![self.pieces_in_play.remove(&square).unwrap()];
+        for position in caught {
+            exploded.extend(self.pieces_in_play.remove(&position));
+        }
+        exploded
     }
 
     fn find_moves(&self, piece: &Piece) -> Vec<Position> {
@@ -613,6 +1441,8 @@ impl Board {
             current_position.row = (current_position.row as i32 + direction.1) as usize;
             match self.validate_square(current_position, piece.colour) {
                 SquareStatus::OutsideBounds | SquareStatus::Occupied => (),
+                // a king capturing would blow itself up
+                SquareStatus::Capturable if self.explosions => (),
                 SquareStatus::Capturable | SquareStatus::Free => moves.push(current_position),
             }
         }
@@ -647,9 +1477,9 @@ impl Board {
     }
 
     fn pawn_has_en_passant_left(&self, pawn: &Piece, step: i32) -> bool {
-        if pawn.colour == PieceColour::White && pawn.position.column == board_columns::A {
-            return false;
-        } else if pawn.colour == PieceColour::Black && pawn.position.column == board_columns::H {
+        if (pawn.colour == PieceColour::White && pawn.position.column == board_columns::A)
+            || (pawn.colour == PieceColour::Black && pawn.position.column == board_columns::H)
+        {
             return false;
         }
 
@@ -669,7 +1499,7 @@ impl Board {
                 return false;
             }
 
-            if piece.special == false {
+            if !piece.special {
                 return false;
             }
         } else {
@@ -680,9 +1510,9 @@ impl Board {
     }
 
     fn pawn_has_en_passant_right(&self, pawn: &Piece, step: i32) -> bool {
-        if pawn.colour == PieceColour::White && pawn.position.column == board_columns::H {
-            return false;
-        } else if pawn.colour == PieceColour::Black && pawn.position.column == board_columns::A {
+        if (pawn.colour == PieceColour::White && pawn.position.column == board_columns::H)
+            || (pawn.colour == PieceColour::Black && pawn.position.column == board_columns::A)
+        {
             return false;
         }
         let right_position: Position = Position::new(pawn.position.row, pawn.position.column + 1);
@@ -701,7 +1531,7 @@ impl Board {
                 return false;
             }
 
-            if piece.special == false {
+            if !piece.special {
                 return false;
             }
         } else {
@@ -810,9 +1640,11 @@ impl Board {
     }
 
     fn validate_square(&self, position: Position, ally_colour: PieceColour) -> SquareStatus {
-        if position.column > board_columns::H || position.column < board_columns::A {
-            SquareStatus::OutsideBounds
-        } else if position.row > 8 || position.row < 1 {
+        if position.column > board_columns::H
+            || position.column < board_columns::A
+            || position.row > 8
+            || position.row < 1
+        {
             SquareStatus::OutsideBounds
         } else {
             match self.pieces_in_play.get(&position) {
@@ -829,7 +1661,46 @@ impl Board {
     }
 
     pub fn promote(&mut self, position: Position, colour: PieceColour, piece_type: PieceType) {
-        let new_piece: Piece = Piece::new(piece_type, position.column, position.row, colour, false);
+        let mut new_piece: Piece =
+            Piece::new(piece_type, position.column, position.row, colour, false);
+        new_piece.promoted = true;
         self.pieces_in_play.insert(position, new_piece);
+        if let Some(last_move) = self.last_move.as_mut() {
+            if last_move.to == position {
+                last_move.promotion = Some(piece_type);
+            }
+        }
+    }
+
+    pub fn last_move(&self) -> Option<CoordinateMove> {
+        self.last_move
+    }
+}
+
+/// Whether two different squares touch, sideways or diagonally.
+fn are_adjacent(a: Position, b: Position) -> bool {
+    a != b && a.row.abs_diff(b.row) <= 1 && a.column.abs_diff(b.column) <= 1
+}
+
+/// The first rank of `colour`, where its king and rooks start.
+fn home_row(colour: PieceColour) -> usize {
+    match colour {
+        PieceColour::White => 1,
+        PieceColour::Black => 8,
+    }
+}
+
+/// Where the king and rook stand after castling, as (king, rook): the g- and f-files king
+/// side and the c- and d-files queen side, wherever they started.
+fn castled_positions(row: usize, direction: &CastleDirection) -> (Position, Position) {
+    match direction {
+        CastleDirection::KingSide => (
+            Position::new(row, board_columns::G),
+            Position::new(row, board_columns::F),
+        ),
+        CastleDirection::QueenSide => (
+            Position::new(row, board_columns::C),
+            Position::new(row, board_columns::D),
+        ),
     }
 }
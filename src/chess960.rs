@@ -0,0 +1,135 @@
+//! Chess960 (Fischer Random) starting positions. The 960 back ranks are numbered as in
+//! Scharnagl's scheme, so position 518 is the standard one. Double Fischer Random gives each
+//! side its own back rank, and any other arrangement can be set up as long as it passes
+//! [`validate_back_rank`].
+
+use crate::{board::Board, error, piece::PieceType};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::io;
+
+pub const POSITION_COUNT: u16 = 960;
+/// The number of the standard starting position.
+pub const STANDARD_INDEX: u16 = 518;
+
+/// Where the two knights go among the five squares left after the bishops and the queen.
+const KNIGHT_PLACEMENTS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+/// The back rank of position `index`, from the a-file to the h-file, or `None` past 959.
+pub fn back_rank(index: u16) -> Option<[PieceType; 8]> {
+    if index >= POSITION_COUNT {
+        return None;
+    }
+    let mut rank: [Option<PieceType>; 8] = [None; 8];
+    let mut n: usize = usize::from(index);
+
+    // the light-squared bishop on b, d, f or h, then the dark-squared one on a, c, e or g
+    rank[(n % 4) * 2 + 1] = Some(PieceType::Bishop);
+    n /= 4;
+    rank[(n % 4) * 2] = Some(PieceType::Bishop);
+    n /= 4;
+
+    place_on_empty(&mut rank, n % 6, PieceType::Queen);
+    n /= 6;
+
+    // the second knight's place counts the squares left once the first is down
+    let (first, second): (usize, usize) = KNIGHT_PLACEMENTS[n];
+    place_on_empty(&mut rank, first, PieceType::Knight);
+    place_on_empty(&mut rank, second - 1, PieceType::Knight);
+
+    // the king always stands between the rooks
+    for piece_type in [PieceType::Rook, PieceType::King, PieceType::Rook] {
+        place_on_empty(&mut rank, 0, piece_type);
+    }
+
+    Some(rank.map(|piece_type| piece_type.unwrap()))
+}
+
+/// The starting position `index`, or `None` past 959.
+pub fn start_position(index: u16) -> Option<Board> {
+    back_rank(index).map(Board::with_back_rank)
+}
+
+/// Double Fischer Random: a different random Chess960 back rank for each side, given as the
+/// positions' numbers for White and Black.
+pub fn double_start_position() -> (u16, u16, Board) {
+    let (white, black): (u16, u16) = (random_index(), random_index());
+    let board: Board = Board::with_back_ranks(back_rank(white).unwrap(), back_rank(black).unwrap());
+    (white, black, board)
+}
+
+/// Reads a back rank written from the a-file to the h-file in piece letters, such as
+/// "RNBQKBNR" (either case), and checks it with [`validate_back_rank`].
+pub fn parse_back_rank(text: &str) -> io::Result<[PieceType; 8]> {
+    let piece_types: Vec<PieceType> = text
+        .trim()
+        .chars()
+        .map(|letter| {
+            PieceType::from_letter(letter)
+                .ok_or_else(|| error::invalid(&format!("{} is not a piece", letter)))
+        })
+        .collect::<io::Result<Vec<PieceType>>>()?;
+    let rank: [PieceType; 8] = piece_types
+        .try_into()
+        .map_err(|_| error::invalid("a back rank has eight pieces"))?;
+    validate_back_rank(&rank)?;
+    Ok(rank)
+}
+
+/// Checks that a back rank has the standard pieces, one king, one queen and two each of rooks,
+/// bishops and knights, with the bishops on opposite colours of square.
+pub fn validate_back_rank(rank: &[PieceType; 8]) -> io::Result<()> {
+    let count = |piece_type: PieceType| rank.iter().filter(|&&p| p == piece_type).count();
+    if count(PieceType::King) != 1 {
+        return Err(error::invalid("a back rank needs exactly one king"));
+    }
+    if count(PieceType::Pawn) > 0 {
+        return Err(error::invalid("pawns cannot start on the back rank"));
+    }
+    // the captured pieces are counted against this set
+    for (piece_type, standard) in [
+        (PieceType::Queen, 1),
+        (PieceType::Rook, 2),
+        (PieceType::Bishop, 2),
+        (PieceType::Knight, 2),
+    ] {
+        if count(piece_type) != standard {
+            return Err(error::invalid(
+                "a back rank needs one queen and two each of rooks, bishops and knights",
+            ));
+        }
+    }
+    let bishop_columns: Vec<usize> = (0..8)
+        .filter(|&column| rank[column] == PieceType::Bishop)
+        .collect();
+    if bishop_columns[0] % 2 == bishop_columns[1] % 2 {
+        return Err(error::invalid("the bishops must be on opposite colours"));
+    }
+    Ok(())
+}
+
+/// A position number picked at random.
+pub fn random_index() -> u16 {
+    (RandomState::new().build_hasher().finish() % u64::from(POSITION_COUNT)) as u16
+}
+
+/// Puts `piece_type` on the `nth` empty square of `rank`.
+fn place_on_empty(rank: &mut [Option<PieceType>; 8], nth: usize, piece_type: PieceType) {
+    let square: &mut Option<PieceType> = rank
+        .iter_mut()
+        .filter(|square| square.is_none())
+        .nth(nth)
+        .unwrap();
+    *square = Some(piece_type);
+}
@@ -0,0 +1,324 @@
+//! Correspondence games kept in a shared save file. Each run shows the position, takes a single
+//! move from the side to move and writes the file back, so no clocks have to keep running
+//! between moves; instead every move must be made within the game's days-per-move limit.
+
+use crate::{
+    board::Board,
+    pgn::MoveRecord,
+    piece::{PieceColour, PieceType},
+    player::{self, PlayerAction},
+    position::{CoordinateMove, MoveResult, Position},
+    render::{self, Frame, Line, Orientation, PlainRenderer, SharedRenderer},
+    result::{self, Termination},
+    save::{Replay, SavedGame},
+    style::Style,
+    svg::{self, SvgOptions},
+};
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Plays a turn at the terminal.
+pub fn play_turn(path: &Path, days_per_move: u64, style: Style) -> io::Result<()> {
+    let renderer: SharedRenderer = Arc::new(Mutex::new(PlainRenderer::new(
+        Box::new(io::stdout()),
+        Orientation::Auto,
+        style,
+    )));
+    play_turn_from(path, days_per_move, io::stdin().lock(), renderer)
+}
+
+/// Plays a turn of the game saved in `path`, starting a new one there if there is none, with
+/// everything the player types read from `input`.
+pub fn play_turn_from(
+    path: &Path,
+    days_per_move: u64,
+    mut input: impl BufRead,
+    renderer: SharedRenderer,
+) -> io::Result<()> {
+    let mut saved: SavedGame = if path.exists() {
+        SavedGame::read(path)?
+    } else {
+        new_game(days_per_move)
+    };
+    let days_per_move: u64 = saved.days_per_move.unwrap_or(days_per_move);
+    saved.days_per_move = Some(days_per_move);
+
+    let Replay {
+        mut board,
+        turn,
+        moves,
+        position_counts,
+        mut variant,
+    } = saved.replay()?;
+
+    renderer.lock().unwrap().frame(&Frame {
+        board: &board,
+        turn,
+        clocks: None,
+        destinations: &[],
+        moves: &moves,
+        notes: (String::new(), String::new()),
+    });
+    print_moves(&renderer, &moves);
+
+    if let Some(result) = &saved.result {
+        show(&renderer, &format!("The game is over: {}", result));
+        return Ok(());
+    }
+
+    let now: u64 = unix_time();
+    let last_move_time: u64 = saved
+        .timestamps
+        .last()
+        .copied()
+        .or(saved.started)
+        .unwrap_or(now);
+    let deadline: u64 = last_move_time + days_per_move * SECONDS_PER_DAY;
+
+    if now > deadline {
+        let result: &str = match turn {
+            PieceColour::White => "0-1 time forfeit",
+            PieceColour::Black => "1-0 time forfeit",
+        };
+        show(
+            &renderer,
+            &format!(
+                "{} did not move before {}: {}",
+                render::colour_name(turn),
+                format_timestamp(deadline),
+                result
+            ),
+        );
+        saved.result = Some(result.to_string());
+        return saved.write(path);
+    }
+
+    if saved.draw_offer == Some(turn.opponent()) {
+        show(
+            &renderer,
+            &format!(
+                "{} offers a draw: type accept, or play a move to decline",
+                render::colour_name(turn.opponent())
+            ),
+        );
+    }
+    show(
+        &renderer,
+        &format!(
+            "{} to move before {}:",
+            render::colour_name(turn),
+            format_timestamp(deadline)
+        ),
+    );
+
+    let mut line: String = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+
+        let (notation, draw_offer): (String, bool) = match PlayerAction::parse(&line) {
+            PlayerAction::Move {
+                notation,
+                draw_offer,
+            } => (notation, draw_offer),
+            PlayerAction::Resign => {
+                let result: &str = match turn {
+                    PieceColour::White => "0-1 resignation",
+                    PieceColour::Black => "1-0 resignation",
+                };
+                saved.result = Some(result.to_string());
+                break;
+            }
+            PlayerAction::Abandon | PlayerAction::Adjourn | PlayerAction::Interrupted => {
+                return Ok(())
+            }
+            PlayerAction::AcceptDraw if saved.draw_offer == Some(turn.opponent()) => {
+                saved.result = Some(String::from("1/2-1/2 agreement"));
+                break;
+            }
+            PlayerAction::AcceptDraw => {
+                show(&renderer, "There is no draw offer to accept");
+                continue;
+            }
+            PlayerAction::ShowMoves(square) => {
+                let destinations: Vec<Position> = board.destinations(turn, square);
+                if destinations.is_empty() {
+                    show(&renderer, &format!("No legal moves from {}", square));
+                }
+                renderer.lock().unwrap().frame(&Frame {
+                    board: &board,
+                    turn,
+                    clocks: None,
+                    destinations: &destinations,
+                    moves: &moves,
+                    notes: (String::new(), String::new()),
+                });
+                continue;
+            }
+            PlayerAction::Save(_) => {
+                show(&renderer, "Correspondence games are saved after every move");
+                continue;
+            }
+            PlayerAction::ExportSvg(path) => {
+                let options: SvgOptions = SvgOptions {
+                    bottom: turn,
+                    ..SvgOptions::default()
+                };
+                let svg: String = svg::to_svg(&board, &svg::with_last_move(&board, &options));
+                match fs::write(&path, svg) {
+                    Ok(()) => show(&renderer, &format!("Wrote {}", path)),
+                    Err(error) => show(&renderer, &format!("Could not write {}: {}", path, error)),
+                }
+                continue;
+            }
+        };
+
+        let board_before_move: Board = board.clone();
+        let move_result: MoveResult = match board.play_notation(turn, &notation) {
+            Ok(MoveResult::PromotionAvailable(square)) => {
+                let piece_type: PieceType = choose_promotion(&mut input, &renderer)?;
+                board.promote(square, turn, piece_type);
+                MoveResult::Success
+            }
+            Ok(move_result @ (MoveResult::Success | MoveResult::Draw)) => move_result,
+            Ok(_) => {
+                show(&renderer, "This move is not legal");
+                continue;
+            }
+            Err(_) => {
+                show(&renderer, "Notation is invalid");
+                continue;
+            }
+        };
+
+        let played: CoordinateMove = board.last_move().unwrap();
+        let variant_win: Option<Termination> = variant.after_move(&board, turn);
+        saved.moves.push((played, draw_offer));
+        saved.timestamps.push(unix_time());
+        saved.draw_offer = if draw_offer { Some(turn) } else { None };
+        saved.position = variant.to_fen(&board, turn.opponent(), saved.moves.len() / 2 + 1);
+        show(
+            &renderer,
+            &format!("You played {}", board_before_move.san(turn, &played)),
+        );
+
+        let repetitions: u32 = position_counts
+            .get(&board.position_key(turn.opponent()))
+            .copied()
+            .unwrap_or(0)
+            + 1;
+        saved.result = result::ending(
+            &mut board,
+            turn,
+            variant.as_ref(),
+            variant_win,
+            move_result,
+            repetitions,
+        )
+        .map(|(winner, termination)| {
+            format!("{} {}", result::score(winner, termination), termination)
+        });
+        break;
+    }
+
+    if let Some(result) = &saved.result {
+        show(&renderer, &format!("The game is over: {}", result));
+    }
+    saved.write(path)
+}
+
+fn new_game(days_per_move: u64) -> SavedGame {
+    let board: Board = Board::new();
+    let start: String = board.to_fen(PieceColour::White, 1);
+    SavedGame {
+        minutes: 0,
+        position: start.clone(),
+        start,
+        moves: Vec::new(),
+        white_clock: Duration::ZERO,
+        black_clock: Duration::ZERO,
+        draw_offer: None,
+        days_per_move: Some(days_per_move),
+        started: Some(unix_time()),
+        timestamps: Vec::new(),
+        result: None,
+        variant: None,
+    }
+}
+
+fn print_moves(renderer: &SharedRenderer, moves: &[MoveRecord]) {
+    let mut line: String = String::new();
+    for (i, record) in moves.iter().enumerate() {
+        if i % 2 == 0 {
+            line.push_str(&format!("{}. ", i / 2 + 1));
+        }
+        line.push_str(&record.san);
+        line.push(' ');
+    }
+    if !line.is_empty() {
+        show(renderer, line.trim_end());
+    }
+}
+
+/// Asks for the piece a pawn promotes to, taking a queen if the input runs out.
+fn choose_promotion(input: &mut impl BufRead, renderer: &SharedRenderer) -> io::Result<PieceType> {
+    show(renderer, "choose piece to promote to:");
+    let mut line: String = String::new();
+    loop {
+        line.clear();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(PieceType::Queen);
+        }
+        match player::promotion_choice(&line) {
+            Some(piece_type) => return Ok(piece_type),
+            None => show(renderer, "Invalid piece symbol"),
+        }
+    }
+}
+
+fn show(renderer: &SharedRenderer, text: &str) {
+    renderer.lock().unwrap().line(Line::Status, text);
+}
+
+fn unix_time() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Formats seconds since the Unix epoch as a UTC date and time.
+fn format_timestamp(timestamp: u64) -> String {
+    // days to civil date, from Howard Hinnant's chrono-compatible algorithms
+    let days: i64 = (timestamp / SECONDS_PER_DAY) as i64 + 719_468;
+    let era: i64 = days.div_euclid(146_097);
+    let day_of_era: i64 = days.rem_euclid(146_097);
+    let year_of_era: i64 =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year: i64 = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month: i64 = (5 * day_of_year + 2) / 153;
+    let day: i64 = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month: i64 = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year: i64 = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    let seconds_today: u64 = timestamp % SECONDS_PER_DAY;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds_today / 3600,
+        (seconds_today % 3600) / 60
+    )
+}
@@ -0,0 +1,51 @@
+use crate::{
+    board::Board,
+    piece::PieceColour,
+    position::{CoordinateMove, MoveResult},
+};
+
+const MATE_SCORE: i32 = 10_000;
+
+/// Picks a move for `colour` with a two ply material search.
+///
+/// Returns `None` when `colour` has no legal move.
+pub fn choose_move(board: &Board, colour: PieceColour) -> Option<CoordinateMove> {
+    let mut search_board: Board = board.clone();
+    let mut best: Option<(CoordinateMove, i32)> = None;
+
+    for candidate in search_board.legal_moves(colour) {
+        let score: i32 = score_move(board, colour, &candidate);
+        if best.is_none_or(|(_, best_score)| score > best_score) {
+            best = Some((candidate, score));
+        }
+    }
+
+    best.map(|(best_move, _)| best_move)
+}
+
+fn score_move(board: &Board, colour: PieceColour, candidate: &CoordinateMove) -> i32 {
+    let enemy_colour: PieceColour = colour.opponent();
+    let mut after_move: Board = board.clone();
+    if let MoveResult::Draw = after_move.play_coordinate_move(colour, candidate) {
+        return 0;
+    }
+
+    let replies: Vec<CoordinateMove> = after_move.legal_moves(enemy_colour);
+    if replies.is_empty() {
+        return if after_move.is_mate(enemy_colour) {
+            MATE_SCORE
+        } else {
+            0
+        };
+    }
+
+    replies
+        .iter()
+        .map(|reply| {
+            let mut after_reply: Board = after_move.clone();
+            after_reply.play_coordinate_move(enemy_colour, reply);
+            after_reply.material(colour) - after_reply.material(enemy_colour)
+        })
+        .min()
+        .unwrap_or(0)
+}
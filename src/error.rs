@@ -0,0 +1,9 @@
+//! The error returned for text the library or the programs cannot make sense of, whether a save
+//! file, a style, a back rank or a command-line option.
+
+use std::io;
+
+/// An `InvalidData` error giving `reason`.
+pub fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
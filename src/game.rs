@@ -1,178 +1,469 @@
 use crate::{
-    board::{Board, CastleDirection},
-    piece::{PieceColour, PieceType},
-    position::{Move, MoveResult, Position},
+    board::Board,
+    error,
+    pgn::{self, MoveRecord},
+    piece::{Piece, PieceColour, PieceType},
+    player::{Interrupt, Notification, Player, PlayerAction},
+    position::{CoordinateMove, MoveResult, Position},
+    render::{self, Frame, Line, Orientation, SharedRenderer},
+    result::{self, GameResult, Termination},
+    save::{Replay, SavedGame},
+    style::Style,
+    svg::{self, SvgOptions},
     timer::Timer,
+    variant::{Standard, Variant},
 };
-use std::process;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
 use std::{io, sync::Arc};
-use std::{io::Write, thread};
-use std::{sync::Mutex, time::Duration};
-
-const ESC: &str = "\x1B[";
+use std::{sync::Mutex, thread, time::Duration};
 const PRECISION: Duration = Duration::from_millis(100);
 
 pub struct GameManager {
     board: Board,
     turn: Arc<Mutex<PieceColour>>,
+    flag: Flag,
     white_timer: Arc<Timer>,
     black_timer: Arc<Timer>,
+    white_player: Box<dyn Player>,
+    black_player: Box<dyn Player>,
+    moves: Vec<MoveRecord>,
+    position_counts: HashMap<String, u32>,
+    draw_offer: Option<PieceColour>,
+    result: String,
+    minutes: u64,
+    start_fen: String,
+    listeners: Arc<Mutex<Vec<EventListener>>>,
+    authoritative_clocks: bool,
+    renderer: SharedRenderer,
+    variant: Box<dyn Variant>,
+}
+
+/// Where a flag fall is recorded, shared by the clock thread, the game and anything else that
+/// learns of one, such as the client of a network game told by the host.
+#[derive(Clone)]
+pub struct Flag {
+    fallen: Arc<Mutex<Option<PieceColour>>>,
+    interrupts: Vec<Interrupt>,
+    listeners: Arc<Mutex<Vec<EventListener>>>,
+}
+
+impl Flag {
+    /// Ends the game on time for `colour`: reports `FlagFell` and stops both players waiting
+    /// for input, so that `start_game` returns as soon as the player to move hands back
+    /// control. Only the first flag to fall counts.
+    pub fn fall(&self, colour: PieceColour) {
+        let mut fallen = self.fallen.lock().unwrap();
+        if fallen.is_some() {
+            return;
+        }
+        *fallen = Some(colour);
+        drop(fallen);
+        GameManager::emit_to(&self.listeners, GameEvent::FlagFell(colour));
+        for interrupt in &self.interrupts {
+            interrupt.raise();
+        }
+    }
+
+    /// The colour whose flag fell, if one has.
+    pub fn fallen(&self) -> Option<PieceColour> {
+        *self.fallen.lock().unwrap()
+    }
+}
+
+/// Called with the colour whose clock ran out, just before the game ends.
+pub type FlagListener = Box<dyn FnOnce(PieceColour) + Send>;
+
+/// Called with every event of the game, in the order they happen. Flag fall is reported from
+/// the thread that saw it (the clock thread, or the one reading from a network opponent),
+/// everything else from the thread running `start_game`.
+pub type EventListener = Box<dyn FnMut(&GameEvent) + Send>;
+
+/// Something that happened during a game, for listeners registered with
+/// [`GameManager::on_event`] or [`GameManager::events`].
+#[derive(Clone, Debug)]
+pub enum GameEvent {
+    /// `colour` played a move. The events describing it follow.
+    Moved {
+        colour: PieceColour,
+        record: MoveRecord,
+    },
+    /// `colour` took a piece, on `square` (or beside it, en passant).
+    Captured {
+        colour: PieceColour,
+        piece_type: PieceType,
+        square: Position,
+    },
+    /// `colour` promoted a pawn on `square`.
+    Promoted {
+        colour: PieceColour,
+        piece_type: PieceType,
+        square: Position,
+    },
+    /// The king of `colour`, on `square`, is in check.
+    Check {
+        colour: PieceColour,
+        square: Position,
+    },
+    /// `colour` offered a draw with the move just played.
+    DrawOffered(PieceColour),
+    /// The clock of `colour` ran out; `GameOver` follows.
+    FlagFell(PieceColour),
+    /// The game ended, without a winner for a draw.
+    GameOver {
+        winner: Option<PieceColour>,
+        termination: Termination,
+    },
 }
 
 impl GameManager {
-    pub fn new(minutes: u64) -> Self {
+    pub fn new(minutes: u64, white_player: Box<dyn Player>, black_player: Box<dyn Player>) -> Self {
+        GameManager::from_position(
+            minutes,
+            Board::new(),
+            PieceColour::White,
+            white_player,
+            black_player,
+        )
+    }
+
+    /// A game starting from `board` with `turn` to move, such as a Chess960 position.
+    pub fn from_position(
+        minutes: u64,
+        board: Board,
+        turn: PieceColour,
+        white_player: Box<dyn Player>,
+        black_player: Box<dyn Player>,
+    ) -> Self {
+        let mut position_counts: HashMap<String, u32> = HashMap::new();
+        position_counts.insert(board.position_key(turn), 1);
+        let listeners: Arc<Mutex<Vec<EventListener>>> = Arc::new(Mutex::new(Vec::new()));
+
         GameManager {
-            board: Board::new(),
-            turn: Arc::new(Mutex::new(PieceColour::White)),
+            start_fen: board.to_fen(turn, 1),
+            board,
+            turn: Arc::new(Mutex::new(turn)),
+            flag: Flag {
+                fallen: Arc::new(Mutex::new(None)),
+                interrupts: [&white_player, &black_player]
+                    .into_iter()
+                    .filter_map(|player| player.interrupt())
+                    .collect(),
+                listeners: Arc::clone(&listeners),
+            },
             white_timer: Arc::new(Timer::new(minutes * 60)),
             black_timer: Arc::new(Timer::new(minutes * 60)),
+            white_player,
+            black_player,
+            moves: Vec::new(),
+            position_counts,
+            draw_offer: None,
+            result: String::from("*"),
+            minutes,
+            listeners,
+            authoritative_clocks: true,
+            renderer: render::for_stdout(false, Orientation::White, Style::default()),
+            variant: Box::new(Standard),
+        }
+    }
+
+    /// Both clocks, for players that keep them in sync with another program.
+    pub fn clocks(&self) -> (Arc<Timer>, Arc<Timer>) {
+        (Arc::clone(&self.white_timer), Arc::clone(&self.black_timer))
+    }
+
+    pub fn draw_offer(&self) -> Option<PieceColour> {
+        self.draw_offer
+    }
+
+    /// Shows the game through `renderer` instead of the default one for stdout.
+    pub fn set_renderer(&mut self, renderer: SharedRenderer) {
+        self.renderer = renderer;
+    }
+
+    /// Plays the game as `variant`. Call it before the first move.
+    pub fn set_variant(&mut self, mut variant: Box<dyn Variant>) {
+        let turn: PieceColour = *self.turn.lock().unwrap();
+        variant.start();
+        variant.set_up(&mut self.board);
+        // the variant's rules can change what counts as the same position
+        self.position_counts = HashMap::from([(self.board.position_key(turn), 1)]);
+        self.start_fen = variant.to_fen(&self.board, turn, 1);
+        self.variant = variant;
+    }
+
+    pub fn variant(&self) -> &dyn Variant {
+        self.variant.as_ref()
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn on_flag(&mut self, listener: FlagListener) {
+        let mut listener: Option<FlagListener> = Some(listener);
+        self.on_event(Box::new(move |event| {
+            if let GameEvent::FlagFell(colour) = event {
+                if let Some(listener) = listener.take() {
+                    listener(*colour);
+                }
+            }
+        }));
+    }
+
+    /// Calls `listener` with every event from now on. Any number of listeners can be added,
+    /// each seeing every event.
+    pub fn on_event(&mut self, listener: EventListener) {
+        self.listeners.lock().unwrap().push(listener);
+    }
+
+    /// The events from now on, for consumers that would rather read them from another thread.
+    pub fn events(&mut self) -> Receiver<GameEvent> {
+        let (sender, receiver): (Sender<GameEvent>, Receiver<GameEvent>) = mpsc::channel();
+        self.on_event(Box::new(move |event| {
+            // nothing to do once the receiver is gone
+            let _ = sender.send(event.clone());
+        }));
+        receiver
+    }
+
+    /// Where a flag fall is recorded, for whoever keeps the clocks when this game follows remote
+    /// ones.
+    pub fn flag(&self) -> Flag {
+        self.flag.clone()
+    }
+
+    /// Leaves flag fall to whoever keeps the clocks authoritative (such as the host of a network
+    /// game); a clock reaching zero here then only shows as 0:0.0.
+    pub fn follow_remote_clocks(&mut self) {
+        self.authoritative_clocks = false;
+    }
+
+    /// Rebuilds an adjourned game by replaying its moves from the saved starting position.
+    pub fn resume(
+        saved: &SavedGame,
+        white_player: Box<dyn Player>,
+        black_player: Box<dyn Player>,
+    ) -> io::Result<Self> {
+        let replay: Replay = saved.replay()?;
+        let (start, start_turn): (Board, PieceColour) = saved
+            .new_variant()?
+            .read_fen(&saved.start)
+            .map_err(|_| error::invalid("bad start position"))?;
+        let mut game: GameManager = GameManager::from_position(
+            saved.minutes,
+            start,
+            start_turn,
+            white_player,
+            black_player,
+        );
+        game.start_fen = saved.start.clone();
+
+        game.board = replay.board;
+        *game.turn.lock().unwrap() = replay.turn;
+        game.moves = replay.moves;
+        game.position_counts = replay.position_counts;
+        game.variant = replay.variant;
+        game.draw_offer = saved.draw_offer;
+        game.white_timer.set_remaining(saved.white_clock);
+        game.black_timer.set_remaining(saved.black_clock);
+        Ok(game)
+    }
+
+    pub fn saved_game(&self) -> SavedGame {
+        let turn: PieceColour = *self.turn.lock().unwrap();
+        SavedGame {
+            minutes: self.minutes,
+            start: self.start_fen.clone(),
+            moves: self
+                .moves
+                .iter()
+                .map(|record| (record.coordinate_move, record.draw_offer))
+                .collect(),
+            position: self
+                .variant
+                .to_fen(&self.board, turn, self.moves.len() / 2 + 1),
+            white_clock: self.white_timer.remaining_duration(),
+            black_clock: self.black_timer.remaining_duration(),
+            draw_offer: self.draw_offer,
+            days_per_move: None,
+            started: None,
+            timestamps: Vec::new(),
+            result: None,
+            variant: (!self.variant.is_standard()).then(|| self.variant.name().to_string()),
         }
     }
 
+    /// The game so far in Portable Game Notation.
+    pub fn pgn(&self) -> String {
+        pgn::to_pgn_from(
+            self.variant.as_ref(),
+            &self.start_fen,
+            &self.moves,
+            &self.result,
+        )
+    }
+
+    /// Shows the clock of the side to move every `PRECISION` until `stop` is dropped, and
+    /// lets the flag fall when a clock this game keeps runs out.
     fn update_timer(
         white_timer: Arc<Timer>,
         black_timer: Arc<Timer>,
         turn: Arc<Mutex<PieceColour>>,
+        flag: Flag,
+        renderer: SharedRenderer,
+        authoritative: bool,
+        stop: Receiver<()>,
     ) {
-        let mut start = Instant::now();
-        loop {
-            let end = Instant::now();
-            if end - start >= PRECISION {
-                print!("{}s", ESC);
-                let current_turn = turn.lock().unwrap();
-                let rem;
-                match *current_turn {
-                    PieceColour::White => {
-                        if white_timer.is_finished() {
-                            print!("{}1E", ESC);
-                            io::stdout().flush().unwrap();
-                            println!("White ran out of time");
-                            break;
-                        }
-                        print!("{}13;0H", ESC);
-                        rem = white_timer.remaining_duration();
-                    }
-                    PieceColour::Black => {
-                        if black_timer.is_finished() {
-                            print!("{}1E", ESC);
-                            io::stdout().flush().unwrap();
-                            println!("Black ran out of time");
-                            break;
-                        }
-                        print!("{}H", ESC);
-                        rem = black_timer.remaining_duration();
-                    }
-                }
-                drop(current_turn);
-                print!("{}2K", ESC);
-                println!(
-                    "{}:{}.{}",
-                    rem.as_secs() / 60,
-                    rem.as_secs() % 60,
-                    (rem.as_millis() - (rem.as_secs() * 1000) as u128) / 100
-                );
-                print!("{}u", ESC);
-                io::stdout().flush().unwrap();
-                start = end;
+        while let Err(RecvTimeoutError::Timeout) = stop.recv_timeout(PRECISION) {
+            let current_turn: PieceColour = *turn.lock().unwrap();
+            let timer: &Timer = match current_turn {
+                PieceColour::White => &white_timer,
+                PieceColour::Black => &black_timer,
+            };
+            let mut renderer = renderer.lock().unwrap();
+            if timer.is_finished() && authoritative {
+                let message: &str = match current_turn {
+                    PieceColour::White => "White ran out of time",
+                    PieceColour::Black => "Black ran out of time",
+                };
+                renderer.line(Line::Status, message);
+                drop(renderer);
+                flag.fall(current_turn);
+                return;
             }
+            renderer.tick(current_turn, timer.remaining_duration());
         }
-        process::exit(0);
     }
 
-    pub fn start_game(&mut self) {
-        let input: io::Stdin = io::stdin();
-        let mut move_notation: String = String::new();
+    /// Plays the game until it ends or is adjourned. When a clock runs out, the game ends as
+    /// soon as the player to move returns from `request_move`, whatever it played.
+    pub fn start_game(&mut self) -> GameResult {
         let mut move_result: MoveResult;
-        let mut movement: Move;
         let w_timer_clone = Arc::clone(&self.white_timer);
         let b_timer_clone = Arc::clone(&self.black_timer);
         let turn_clone = Arc::clone(&self.turn);
+        let flag: Flag = self.flag.clone();
+        let renderer_clone: SharedRenderer = Arc::clone(&self.renderer);
+        let authoritative_clocks: bool = self.authoritative_clocks;
+        // paused here rather than on the clock thread, which could otherwise pause it again
+        // after a quick first move has resumed it
+        match *self.turn.lock().unwrap() {
+            PieceColour::White => self.black_timer.pause(),
+            PieceColour::Black => self.white_timer.pause(),
+        }
+        let (stop, stopped): (Sender<()>, Receiver<()>) = mpsc::channel();
         self.print();
-        thread::spawn(move || {
+        let clock: JoinHandle<()> = thread::spawn(move || {
             w_timer_clone.countdown_start();
-            b_timer_clone.pause();
             b_timer_clone.countdown_start();
-            GameManager::update_timer(w_timer_clone, b_timer_clone, turn_clone);
+            GameManager::update_timer(
+                w_timer_clone,
+                b_timer_clone,
+                turn_clone,
+                flag,
+                renderer_clone,
+                authoritative_clocks,
+                stopped,
+            );
         });
-        loop {
+        let (winner, termination): (Option<PieceColour>, Termination) = loop {
             let turn_lock = self.turn.lock().unwrap();
             let mut turn = *turn_lock;
             drop(turn_lock);
-            print!("{}14;0H", ESC);
-            println!("your move:");
-            print!("{}2K", ESC);
-            io::stdout().flush().unwrap();
-            move_notation.clear();
-            input.read_line(&mut move_notation).unwrap();
-            move_notation = move_notation.trim().to_string();
-            if move_notation.to_ascii_lowercase() == "o-o" {
-                move_result = self.board.castle(turn, CastleDirection::KingSide);
-            } else if move_notation.to_ascii_lowercase() == "o-o-o" {
-                move_result = self.board.castle(turn, CastleDirection::QueenSide);
-            } else if move_notation.to_ascii_lowercase() == "resign" {
-                self.white_timer.pause();
-                self.black_timer.pause();
-                print!("{}2K", ESC);
-                io::stdout().flush().unwrap();
-                match turn {
-                    PieceColour::White => println!("Black won"),
-                    PieceColour::Black => println!("White won"),
+            let action: PlayerAction = match turn {
+                PieceColour::White => self.white_player.request_move(&self.board, turn),
+                PieceColour::Black => self.black_player.request_move(&self.board, turn),
+            };
+            if let Some(loser) = self.flag.fallen() {
+                break (Some(loser.opponent()), Termination::Timeout);
+            }
+            let (move_notation, draw_offer): (String, bool) = match action {
+                PlayerAction::Move {
+                    notation,
+                    draw_offer,
+                } => (notation, draw_offer),
+                PlayerAction::Resign => break (Some(turn.opponent()), Termination::Resignation),
+                PlayerAction::Abandon => break (Some(turn.opponent()), Termination::Abandonment),
+                PlayerAction::Adjourn => {
+                    self.white_timer.pause();
+                    self.black_timer.pause();
+                    break (None, Termination::Adjourned);
                 }
-                break;
-            } else if move_notation.to_ascii_lowercase() == "draw" {
-                self.white_timer.pause();
-                self.black_timer.pause();
-                print!("{}2K", ESC);
-                io::stdout().flush().unwrap();
-                println!("Draw");
-                break;
-            } else {
-                movement = match Move::from_notation(&move_notation) {
-                    Ok(movement) => movement,
-                    Err(_) => {
-                        print!("{}2K", ESC);
-                        println!("Notation is invalid");
-                        continue;
+                // only a flag fall raises the interrupt, and that ended the game above
+                PlayerAction::Interrupted => continue,
+                PlayerAction::Save(path) => {
+                    self.white_timer.pause();
+                    self.black_timer.pause();
+                    match self.saved_game().write(Path::new(&path)) {
+                        Ok(()) => {
+                            self.show(&format!("Game adjourned, resume it with --resume {}", path));
+                            break (None, Termination::Adjourned);
+                        }
+                        Err(error) => {
+                            self.player(turn)
+                                .notify(&Notification::SaveFailed(error.to_string()));
+                            match turn {
+                                PieceColour::White => self.white_timer.resume(),
+                                PieceColour::Black => self.black_timer.resume(),
+                            }
+                            continue;
+                        }
                     }
-                };
-                move_result = self.board.make_move(turn, &movement);
-            }
-
-            match move_result {
-                MoveResult::AmbiguousMove => {
-                    print!("{}2K", ESC);
-                    println!("Multiple pieces can make this move, consider specifying the starting row or column");
-                    continue;
                 }
-                MoveResult::Checked => {
-                    print!("{}2K", ESC);
-                    println!("Cannot make this move due to check");
+                PlayerAction::ShowMoves(square) => {
+                    self.show_moves(turn, square);
                     continue;
                 }
-                MoveResult::ImpossibleMove => {
-                    print!("{}2K", ESC);
-                    println!("This move is not legal");
+                PlayerAction::ExportSvg(path) => {
+                    self.export_svg(&path);
                     continue;
                 }
-                MoveResult::MissingPiece => {
-                    print!("{}2K", ESC);
-                    println!("No piece can make this move");
-                    continue;
+                PlayerAction::AcceptDraw => {
+                    if self.draw_offer != Some(turn.opponent()) {
+                        self.player(turn).notify(&Notification::NoDrawOffer);
+                        continue;
+                    }
+                    break (None, Termination::Agreement);
                 }
-                MoveResult::PiecePinned => {
-                    print!("{}2K", ESC);
-                    println!("This piece is pinned");
+            };
+
+            let board_before_move: Board = self.board.clone();
+            move_result = match self.board.play_notation(turn, &move_notation) {
+                Ok(move_result) => move_result,
+                Err(_) => {
+                    self.player(turn).notify(&Notification::InvalidNotation);
                     continue;
                 }
-                MoveResult::Draw => {
-                    self.print();
-                    println!("Draw");
-                    break;
+            };
+
+            match move_result {
+                MoveResult::AmbiguousMove
+                | MoveResult::Checked
+                | MoveResult::ImpossibleMove
+                | MoveResult::MissingPiece
+                | MoveResult::PiecePinned => {
+                    self.player(turn)
+                        .notify(&Notification::MoveRejected(move_result));
+                    continue;
                 }
                 MoveResult::PromotionAvailable(square) => self.handle_promotion(turn, square),
-                MoveResult::Success => (),
+                MoveResult::Draw | MoveResult::Success => (),
+            }
+
+            let repetitions: u32 = self.record_move(&board_before_move, turn, draw_offer);
+            self.report_move(&board_before_move, turn, draw_offer);
+            let variant_win: Option<Termination> = self.variant.after_move(&self.board, turn);
+
+            // playing a move declines any draw the opponent had offered
+            if self.draw_offer == Some(turn.opponent()) {
+                self.player(turn.opponent())
+                    .notify(&Notification::DrawDeclined);
             }
+            self.draw_offer = if draw_offer { Some(turn) } else { None };
 
             let mut turn_lock = self.turn.lock().unwrap();
             *turn_lock = match *turn_lock {
@@ -191,73 +482,218 @@ impl GameManager {
             turn = *turn_lock;
             drop(turn_lock);
 
-            if self.board.is_stalemate(turn) {
-                self.print();
-                println!("Stalemate");
-                break;
+            self.print();
+            let record: MoveRecord = self.moves.last().unwrap().clone();
+            self.player(turn.opponent())
+                .notify(&Notification::MoveAccepted(record.clone()));
+            self.player(turn)
+                .notify(&Notification::OpponentMoved(record));
+
+            if let Some(ending) = result::ending(
+                &mut self.board,
+                turn.opponent(),
+                self.variant.as_ref(),
+                variant_win,
+                move_result,
+                repetitions,
+            ) {
+                break ending;
             }
 
-            if self.board.is_mate(turn) {
-                self.white_timer.pause();
-                self.black_timer.pause();
-                self.print();
-                match turn {
-                    PieceColour::White => println!("Black won"),
-                    PieceColour::Black => println!("White won"),
-                }
-                break;
+            if self.draw_offer.is_some() {
+                self.player(turn).notify(&Notification::DrawOffered);
             }
-            self.print();
+        };
+
+        drop(stop);
+        let _ = clock.join();
+        if termination == Termination::Adjourned {
+            let notification: Notification = Notification::GameOver {
+                winner,
+                termination,
+            };
+            self.white_player.notify(&notification);
+            self.black_player.notify(&notification);
+        } else {
+            self.end_game(winner, termination);
+        }
+        GameResult {
+            winner,
+            termination,
+            board: self.board.clone(),
+            turn: *self.turn.lock().unwrap(),
+            moves: self.moves.clone(),
         }
     }
 
+    /// Adds the move just played to the history, returning how many times the resulting
+    /// position has now occurred.
+    fn record_move(
+        &mut self,
+        board_before_move: &Board,
+        turn: PieceColour,
+        draw_offer: bool,
+    ) -> u32 {
+        let played: CoordinateMove = self.board.last_move().unwrap();
+        self.moves.push(MoveRecord {
+            coordinate_move: played,
+            san: board_before_move.san(turn, &played),
+            draw_offer,
+        });
+
+        let repetitions: &mut u32 = self
+            .position_counts
+            .entry(self.board.position_key(turn.opponent()))
+            .or_insert(0);
+        *repetitions += 1;
+        *repetitions
+    }
+
+    /// Tells the listeners about the move just played and what it did.
+    fn report_move(&self, board_before_move: &Board, turn: PieceColour, draw_offer: bool) {
+        let record: MoveRecord = self.moves.last().unwrap().clone();
+        let played: CoordinateMove = record.coordinate_move;
+        self.emit(GameEvent::Moved {
+            colour: turn,
+            record,
+        });
+        if let Some((piece_type, square)) = captured_piece(board_before_move, turn, &played) {
+            self.emit(GameEvent::Captured {
+                colour: turn,
+                piece_type,
+                square,
+            });
+        }
+        if let Some(piece_type) = played.promotion {
+            self.emit(GameEvent::Promoted {
+                colour: turn,
+                piece_type,
+                square: played.to,
+            });
+        }
+        if let Some(square) = self.board.checked_king(turn.opponent()) {
+            self.emit(GameEvent::Check {
+                colour: turn.opponent(),
+                square,
+            });
+        }
+        if draw_offer {
+            self.emit(GameEvent::DrawOffered(turn));
+        }
+    }
+
+    fn emit(&self, event: GameEvent) {
+        GameManager::emit_to(&self.listeners, event);
+    }
+
+    fn emit_to(listeners: &Mutex<Vec<EventListener>>, event: GameEvent) {
+        for listener in listeners.lock().unwrap().iter_mut() {
+            listener(&event);
+        }
+    }
+
+    fn player(&mut self, colour: PieceColour) -> &mut dyn Player {
+        match colour {
+            PieceColour::White => self.white_player.as_mut(),
+            PieceColour::Black => self.black_player.as_mut(),
+        }
+    }
+
+    fn end_game(&mut self, winner: Option<PieceColour>, termination: Termination) {
+        self.white_timer.pause();
+        self.black_timer.pause();
+        self.result = result::score(winner, termination).to_string();
+        let message: &str = match winner {
+            Some(PieceColour::White) => "White won",
+            Some(PieceColour::Black) => "Black won",
+            None => "Draw",
+        };
+        self.show(&format!("{} by {}", message, termination));
+        let notification: Notification = Notification::GameOver {
+            winner,
+            termination,
+        };
+        self.white_player.notify(&notification);
+        self.black_player.notify(&notification);
+        self.emit(GameEvent::GameOver {
+            winner,
+            termination,
+        });
+    }
+
     fn print(&self) {
-        print!("{}2J", ESC);
-        print!("{}H", ESC);
-        let mut rem = self.black_timer.remaining_duration();
-        println!(
-            "{}:{}.{}",
-            rem.as_secs() / 60,
-            rem.as_secs() % 60,
-            (rem.as_millis() - (rem.as_secs() * 1000) as u128) / 100
-        );
-        print!("{}1;0H", ESC);
-        self.board.print();
-        rem = self.white_timer.remaining_duration();
-        print!("{}13;0H", ESC);
-        io::stdout().flush().unwrap();
-        println!(
-            "{}:{}.{}",
-            rem.as_secs() / 60,
-            rem.as_secs() % 60,
-            (rem.as_millis() - (rem.as_secs() * 1000) as u128) / 100
+        self.print_with_destinations(&[]);
+    }
+
+    fn print_with_destinations(&self, destinations: &[Position]) {
+        let clocks: (Duration, Duration) = (
+            self.white_timer.remaining_duration(),
+            self.black_timer.remaining_duration(),
         );
+        self.renderer.lock().unwrap().frame(&Frame {
+            board: &self.board,
+            turn: *self.turn.lock().unwrap(),
+            clocks: Some(clocks),
+            destinations,
+            moves: &self.moves,
+            notes: (
+                self.variant.note(&self.board, PieceColour::White),
+                self.variant.note(&self.board, PieceColour::Black),
+            ),
+        });
     }
 
-    fn handle_promotion(&mut self, turn: PieceColour, position: Position) {
-        println!("choose piece to promote to:");
-        let input: io::Stdin = io::stdin();
-        let mut piece_string: String = String::new();
-        loop {
-            piece_string.clear();
-            input.read_line(&mut piece_string).unwrap();
-            piece_string = piece_string.to_ascii_lowercase();
-            let piece_symbol: char = piece_string.chars().nth(0).unwrap();
-            let piece_type: PieceType = match piece_symbol {
-                'r' => PieceType::Rook,
-                'q' => PieceType::Queen,
-                'n' | 'k' => PieceType::Knight,
-                'b' => PieceType::Bishop,
-                _ => {
-                    print!("Invalid piece symbol");
-                    print!("{}1F", ESC);
-                    print!("{}2K", ESC);
-                    io::stdout().flush().unwrap();
-                    continue;
-                }
-            };
-            self.board.promote(position, turn, piece_type);
-            return;
+    /// Redraws the board with the squares the piece on `square` can move to.
+    fn show_moves(&self, turn: PieceColour, square: Position) {
+        let destinations: Vec<Position> = self.board.destinations(turn, square);
+        self.print_with_destinations(&destinations);
+        if destinations.is_empty() {
+            self.show(&format!("No legal moves from {}", square));
         }
     }
+
+    /// Writes the current position, seen from the side to move, as an SVG diagram.
+    fn export_svg(&self, path: &str) {
+        let options: SvgOptions = SvgOptions {
+            bottom: *self.turn.lock().unwrap(),
+            ..SvgOptions::default()
+        };
+        let svg: String = svg::to_svg(&self.board, &svg::with_last_move(&self.board, &options));
+        match fs::write(path, svg) {
+            Ok(()) => self.show(&format!("Wrote {}", path)),
+            Err(error) => self.show(&format!("Could not write {}: {}", path, error)),
+        }
+    }
+
+    fn show(&self, text: &str) {
+        self.renderer.lock().unwrap().line(Line::Status, text);
+    }
+
+    fn handle_promotion(&mut self, turn: PieceColour, position: Position) {
+        let piece_type: PieceType = match turn {
+            PieceColour::White => self.white_player.choose_promotion(&self.board, turn),
+            PieceColour::Black => self.black_player.choose_promotion(&self.board, turn),
+        };
+        self.board.promote(position, turn, piece_type);
+    }
+}
+
+/// The piece `colour` took with `played`, and where it stood.
+fn captured_piece(
+    board_before_move: &Board,
+    colour: PieceColour,
+    played: &CoordinateMove,
+) -> Option<(PieceType, Position)> {
+    if let Some(piece) = board_before_move.piece_at(played.to) {
+        return (piece.colour != colour).then_some((piece.piece_type, played.to));
+    }
+    // a pawn moving diagonally onto an empty square takes en passant
+    let mover: &Piece = board_before_move.piece_at(played.from)?;
+    if mover.piece_type == PieceType::Pawn && played.from.column != played.to.column {
+        return Some((
+            PieceType::Pawn,
+            Position::new(played.from.row, played.to.column),
+        ));
+    }
+    None
 }
@@ -0,0 +1,409 @@
+//! Animated GIF replays of a game, drawn and encoded here without any external tools, for
+//! sharing games in chat. Each position is one frame, the last one held a little longer.
+//!
+//! `chess gif` makes them from the command line.
+//!
+//! Pieces are drawn from the 16 by 16 silhouettes below, scaled to the squares and outlined.
+
+use crate::{
+    board::Board,
+    piece::{PieceColour, PieceType},
+    position::Position,
+};
+use std::collections::HashMap;
+
+/// The final position stays on screen this many times longer than the others.
+const FINAL_FRAME_HOLD: u32 = 3;
+/// The colour table: the squares, the squares of the last move, then the pieces.
+const LIGHT: u8 = 0;
+const DARK: u8 = 1;
+const LIGHT_HIGHLIGHT: u8 = 2;
+const DARK_HIGHLIGHT: u8 = 3;
+const WHITE_PIECE: u8 = 4;
+const BLACK_PIECE: u8 = 5;
+const OUTLINE: u8 = 6;
+/// Eight colours, so pixels need three bits.
+const COLOUR_BITS: u8 = 3;
+const MAX_CODE: u16 = 4096;
+
+/// The colours of the squares, as (light, dark, light highlighted, dark highlighted).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Theme {
+    squares: [[u8; 3]; 4],
+}
+
+impl Theme {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let squares: [[u8; 3]; 4] = match name {
+            "brown" => [
+                [0xf0, 0xd9, 0xb5],
+                [0xb5, 0x88, 0x63],
+                [0xcd, 0xd2, 0x6a],
+                [0xaa, 0xa2, 0x3a],
+            ],
+            "green" => [
+                [0xee, 0xee, 0xd2],
+                [0x76, 0x96, 0x56],
+                [0xf6, 0xf6, 0x69],
+                [0xba, 0xca, 0x2b],
+            ],
+            "blue" => [
+                [0xde, 0xe3, 0xe6],
+                [0x8c, 0xa2, 0xad],
+                [0xc3, 0xd8, 0x87],
+                [0x92, 0xb1, 0x66],
+            ],
+            "grey" => [
+                [0xe0, 0xe0, 0xe0],
+                [0x9e, 0x9e, 0x9e],
+                [0xe8, 0xe0, 0x90],
+                [0xb0, 0xa8, 0x58],
+            ],
+            _ => return None,
+        };
+        Some(Theme { squares })
+    }
+
+    fn palette(&self) -> [[u8; 3]; 8] {
+        [
+            self.squares[0],
+            self.squares[1],
+            self.squares[2],
+            self.squares[3],
+            [0xff, 0xff, 0xff],
+            [0x3a, 0x3a, 0x3a],
+            [0x00, 0x00, 0x00],
+            [0x00, 0x00, 0x00],
+        ]
+    }
+}
+
+pub struct GifOptions {
+    /// How long each position is shown, in milliseconds.
+    pub delay: u32,
+    /// The width and height of the board in pixels, rounded down to a multiple of eight.
+    pub size: u16,
+    pub theme: Theme,
+    /// The side drawn at the bottom.
+    pub bottom: PieceColour,
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        GifOptions {
+            delay: 1000,
+            size: 320,
+            theme: Theme::from_name("brown").unwrap(),
+            bottom: PieceColour::White,
+        }
+    }
+}
+
+/// Encodes one frame per board as a looping animated GIF.
+pub fn to_gif(boards: &[Board], options: &GifOptions) -> Vec<u8> {
+    let square: usize = (options.size / 8) as usize;
+    let size: u16 = (square * 8) as u16;
+    let mut gif: Vec<u8> = Vec::new();
+    gif.extend_from_slice(b"GIF89a");
+    gif.extend_from_slice(&size.to_le_bytes());
+    gif.extend_from_slice(&size.to_le_bytes());
+    // a global colour table of 2^3 entries with 8 bits per primary colour
+    gif.extend_from_slice(&[0xf0 | (COLOUR_BITS - 1), 0, 0]);
+    for colour in options.theme.palette() {
+        gif.extend_from_slice(&colour);
+    }
+    // loop forever
+    gif.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    for (i, board) in boards.iter().enumerate() {
+        let mut delay: u32 = options.delay / 10;
+        if i + 1 == boards.len() {
+            delay *= FINAL_FRAME_HOLD;
+        }
+        let delay: u16 = delay.min(u16::MAX as u32) as u16;
+        gif.extend_from_slice(&[0x21, 0xf9, 0x04, 0x00]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0x00, 0x00]);
+
+        gif.push(0x2c);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&size.to_le_bytes());
+        gif.extend_from_slice(&size.to_le_bytes());
+        gif.push(0x00);
+
+        gif.push(COLOUR_BITS);
+        let data: Vec<u8> = compress(&draw(board, square, options.bottom), COLOUR_BITS);
+        for block in data.chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0x00);
+    }
+    gif.push(0x3b);
+    gif
+}
+
+/// The colour table index of every pixel of `board`, row by row from the top left.
+fn draw(board: &Board, square: usize, bottom: PieceColour) -> Vec<u8> {
+    let size: usize = square * 8;
+    let mut pixels: Vec<u8> = vec![0; size * size];
+    let highlighted: Vec<Position> = board
+        .last_move()
+        .map_or(Vec::new(), |last_move| vec![last_move.from, last_move.to]);
+    // thicker outlines for bigger pieces
+    let outline: usize = (square / 40).max(1);
+
+    for row_from_top in 0..8 {
+        for column_from_left in 0..8 {
+            let position: Position = match bottom {
+                PieceColour::White => Position::new(8 - row_from_top, column_from_left + 1),
+                PieceColour::Black => Position::new(row_from_top + 1, 8 - column_from_left),
+            };
+            let dark: bool = (position.row + position.column).is_multiple_of(2);
+            let background: u8 = match (dark, highlighted.contains(&position)) {
+                (false, false) => LIGHT,
+                (true, false) => DARK,
+                (false, true) => LIGHT_HIGHLIGHT,
+                (true, true) => DARK_HIGHLIGHT,
+            };
+            let piece: Option<(&Silhouette, u8)> = board.piece_at(position).map(|p| {
+                let fill: u8 = match p.colour {
+                    PieceColour::White => WHITE_PIECE,
+                    PieceColour::Black => BLACK_PIECE,
+                };
+                (silhouette(p.piece_type), fill)
+            });
+
+            for y in 0..square {
+                for x in 0..square {
+                    let colour: u8 = match piece {
+                        Some((shape, fill)) if covers(shape, square, x, y) => fill,
+                        Some((shape, _)) if near(shape, square, x, y, outline) => OUTLINE,
+                        _ => background,
+                    };
+                    let pixel_row: usize = row_from_top * square + y;
+                    pixels[pixel_row * size + column_from_left * square + x] = colour;
+                }
+            }
+        }
+    }
+    pixels
+}
+
+/// Whether pixel (`x`, `y`) of a square `square` pixels wide falls on the silhouette.
+fn covers(shape: &Silhouette, square: usize, x: usize, y: usize) -> bool {
+    shape[y * 16 / square].as_bytes()[x * 16 / square] == b'x'
+}
+
+/// Whether the silhouette is within `distance` pixels of (`x`, `y`), for drawing its outline.
+fn near(shape: &Silhouette, square: usize, x: usize, y: usize, distance: usize) -> bool {
+    let (x_range, y_range) = (
+        x.saturating_sub(distance)..=(x + distance).min(square - 1),
+        y.saturating_sub(distance)..=(y + distance).min(square - 1),
+    );
+    y_range.clone().any(|near_y| {
+        x_range
+            .clone()
+            .any(|near_x| covers(shape, square, near_x, near_y))
+    })
+}
+
+/// GIF's variant of LZW compression, packing the codes into bytes from the lowest bit up.
+fn compress(pixels: &[u8], minimum_code_size: u8) -> Vec<u8> {
+    let clear: u16 = 1 << minimum_code_size;
+    let end: u16 = clear + 1;
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code: u16 = end + 1;
+    let mut code_size: u8 = minimum_code_size + 1;
+    let mut output: BitWriter = BitWriter::default();
+
+    output.write(clear, code_size);
+    let mut prefix: Option<u16> = None;
+    for &pixel in pixels {
+        let current: u16 = match prefix {
+            None => {
+                prefix = Some(pixel as u16);
+                continue;
+            }
+            Some(current) => current,
+        };
+        if let Some(&code) = codes.get(&(current, pixel)) {
+            prefix = Some(code);
+            continue;
+        }
+        output.write(current, code_size);
+        if next_code < MAX_CODE {
+            codes.insert((current, pixel), next_code);
+            next_code += 1;
+            // the decoder widens its codes once its table outgrows them, one code later
+            if next_code > (1 << code_size) && code_size < 12 {
+                code_size += 1;
+            }
+        } else {
+            output.write(clear, code_size);
+            codes.clear();
+            next_code = end + 1;
+            code_size = minimum_code_size + 1;
+        }
+        prefix = Some(pixel as u16);
+    }
+    if let Some(current) = prefix {
+        output.write(current, code_size);
+    }
+    output.write(end, code_size);
+    output.finish()
+}
+
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u8,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u8) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+/// A piece as 16 rows of 16 pixels, `x` where the piece is.
+type Silhouette = [&'static str; 16];
+
+fn silhouette(piece_type: PieceType) -> &'static Silhouette {
+    match piece_type {
+        PieceType::King => &KING,
+        PieceType::Queen => &QUEEN,
+        PieceType::Rook => &ROOK,
+        PieceType::Bishop => &BISHOP,
+        PieceType::Knight => &KNIGHT,
+        PieceType::Pawn => &PAWN,
+    }
+}
+
+const KING: Silhouette = [
+    ".......xx.......",
+    "......xxxx......",
+    ".......xx.......",
+    "...xxx.xx.xxx...",
+    "..xxxxxxxxxxxx..",
+    "..xxxxxxxxxxxx..",
+    "..xxxxxxxxxxxx..",
+    "...xxxxxxxxxx...",
+    "....xxxxxxxx....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    "....xxxxxxxx....",
+    "...xxxxxxxxxx...",
+    "..xxxxxxxxxxxx..",
+    "..xxxxxxxxxxxx..",
+    "................",
+];
+
+const QUEEN: Silhouette = [
+    "................",
+    "..x...x..x...x..",
+    "..x..xx..xx..x..",
+    "..xx.xx..xx.xx..",
+    "..xxxxxxxxxxxx..",
+    "...xxxxxxxxxx...",
+    "...xxxxxxxxxx...",
+    "....xxxxxxxx....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    "....xxxxxxxx....",
+    "....xxxxxxxx....",
+    "...xxxxxxxxxx...",
+    "..xxxxxxxxxxxx..",
+    "..xxxxxxxxxxxx..",
+    "................",
+];
+
+const ROOK: Silhouette = [
+    "................",
+    "................",
+    "...xx.xxxx.xx...",
+    "...xxxxxxxxxx...",
+    "...xxxxxxxxxx...",
+    "....xxxxxxxx....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    "....xxxxxxxx....",
+    "...xxxxxxxxxx...",
+    "..xxxxxxxxxxxx..",
+    "..xxxxxxxxxxxx..",
+    "................",
+];
+
+const BISHOP: Silhouette = [
+    "................",
+    ".......xx.......",
+    "......xxxx......",
+    ".....xxx.xx.....",
+    ".....xx.xxx.....",
+    ".....xxxxxx.....",
+    "......xxxx......",
+    ".......xx.......",
+    "......xxxx......",
+    ".....xxxxxx.....",
+    "......xxxx......",
+    ".....xxxxxx.....",
+    "....xxxxxxxx....",
+    "...xxxxxxxxxx...",
+    "...xxxxxxxxxx...",
+    "................",
+];
+
+const KNIGHT: Silhouette = [
+    "................",
+    "................",
+    ".......x.x......",
+    "......xxxxx.....",
+    ".....xxxxxxx....",
+    "....xxxxxxxxx...",
+    "...xxxxxxxxxx...",
+    "...xxx..xxxxx...",
+    ".......xxxxxx...",
+    "......xxxxxx....",
+    ".....xxxxxxx....",
+    ".....xxxxxxx....",
+    "....xxxxxxxxx...",
+    "...xxxxxxxxxxx..",
+    "...xxxxxxxxxxx..",
+    "................",
+];
+
+const PAWN: Silhouette = [
+    "................",
+    "................",
+    "................",
+    "......xxxx......",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    "......xxxx......",
+    ".......xx.......",
+    "......xxxx......",
+    ".....xxxxxx.....",
+    ".....xxxxxx.....",
+    "....xxxxxxxx....",
+    "...xxxxxxxxxx...",
+    "...xxxxxxxxxx...",
+    "................",
+    "................",
+];
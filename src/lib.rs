@@ -0,0 +1,65 @@
+//! Chess rules, notation and clocks, with everything the `chess` and `chess-server` programs
+//! build on top of them.
+//!
+//! The core of the library is [`Board`], which checks and plays moves given as a [`Move`] in
+//! algebraic notation or as a [`CoordinateMove`], reads and writes FEN and names moves in SAN.
+//! [`pgn`] reads and writes whole games, [`Timer`] is a pausable countdown clock and
+//! [`GameManager`] runs a timed game between two [`Player`]s, reporting each move, check,
+//! capture, promotion, draw offer and the end of the game as a [`game::GameEvent`] to anything
+//! subscribed with `on_event` or `events`.
+//!
+//! ```
+//! use chess::{Board, MoveResult, PieceColour};
+//!
+//! let mut board = Board::new();
+//! assert_eq!(board.play_notation(PieceColour::White, "e4").unwrap(), MoveResult::Success);
+//! assert_eq!(
+//!     board.to_fen(PieceColour::Black, 1),
+//!     "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+//! );
+//! ```
+//!
+//! With the `serde` feature, squares, pieces, moves, [`MoveResult`] and the game record
+//! [`save::SavedGame`] implement `Serialize` and `Deserialize`.
+//!
+//! The programs' front ends (the terminal UI, network play, the web board, XBoard, correspondence
+//! games and the engine) are compiled into the library so both programs can share them, but are
+//! hidden from the documentation and not part of its stable API.
+
+pub mod board;
+pub mod chess960;
+#[doc(hidden)]
+pub mod correspondence;
+#[doc(hidden)]
+pub mod engine;
+pub mod error;
+pub mod game;
+pub mod gif;
+#[doc(hidden)]
+pub mod network;
+pub mod pgn;
+pub mod piece;
+pub mod player;
+pub mod position;
+pub mod render;
+pub mod result;
+pub mod save;
+pub mod style;
+pub mod svg;
+pub mod timer;
+#[cfg(feature = "tui")]
+#[doc(hidden)]
+pub mod tui;
+pub mod variant;
+#[doc(hidden)]
+pub mod web;
+#[doc(hidden)]
+pub mod xboard;
+
+pub use board::Board;
+pub use game::GameManager;
+pub use piece::{Piece, PieceColour, PieceType};
+pub use player::Player;
+pub use position::{CoordinateMove, Move, MoveResult, NotationError, Position};
+pub use result::{GameResult, Termination};
+pub use timer::Timer;
@@ -1,22 +1,615 @@
-use game::GameManager;
+use chess::board::{Board, STANDARD_BACK_RANK};
+use chess::game::GameManager;
+use chess::gif::{GifOptions, Theme};
+use chess::network::{RemoteLink, RemotePlayer};
+use chess::pgn::{self, PgnGame};
+use chess::piece::PieceColour;
+use chess::player::{EnginePlayer, HumanPlayer, Player, ScriptedPlayer};
+use chess::render::{self, Line, Orientation, SharedRenderer};
+use chess::result::GameResult;
+use chess::save::SavedGame;
+use chess::style::Style;
+use chess::svg::SvgOptions;
+#[cfg(feature = "tui")]
+use chess::tui;
+use chess::variant::{self, Variant};
+use chess::web::WebServer;
+use chess::xboard::XBoardSession;
+use chess::{chess960, correspondence, error, gif, svg, web};
+use chess::{CoordinateMove, Position};
 use std::env::args;
-mod board;
-mod game;
-mod piece;
-mod position;
-mod timer;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+use std::sync::Arc;
+
+struct Options {
+    minutes: u64,
+    xboard: bool,
+    white: String,
+    black: String,
+    pgn: Option<String>,
+    resume: Option<String>,
+    correspondence: Option<String>,
+    days_per_move: u64,
+    host: Option<u16>,
+    connect: Option<String>,
+    web_port: u16,
+    plain: bool,
+    tui: bool,
+    orientation: Option<Orientation>,
+    pieces: Option<String>,
+    squares: Option<String>,
+    /// Some for a Chess960 game, with the position number (or "double") if one was chosen.
+    chess960: Option<Option<String>>,
+    white_rank: Option<String>,
+    black_rank: Option<String>,
+    variant: Option<String>,
+}
+
+fn parse_args() -> Options {
+    let mut options: Options = Options {
+        minutes: 10,
+        xboard: false,
+        white: String::from("human"),
+        black: String::from("human"),
+        pgn: None,
+        resume: None,
+        correspondence: None,
+        days_per_move: 3,
+        host: None,
+        connect: None,
+        web_port: web::DEFAULT_PORT,
+        plain: false,
+        tui: false,
+        orientation: None,
+        pieces: None,
+        squares: None,
+        chess960: None,
+        white_rank: None,
+        black_rank: None,
+        variant: None,
+    };
+    let mut args = args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--xboard" => options.xboard = true,
+            "--plain" => options.plain = true,
+            "--tui" => options.tui = true,
+            "--orientation" => {
+                options.orientation = args.next().and_then(|name| Orientation::from_name(&name))
+            }
+            "--pieces" => options.pieces = args.next(),
+            "--squares" => options.squares = args.next(),
+            "--white" => options.white = args.next().unwrap_or_default(),
+            "--black" => options.black = args.next().unwrap_or_default(),
+            "--pgn" => options.pgn = args.next(),
+            "--resume" => options.resume = args.next(),
+            "--correspondence" => options.correspondence = args.next(),
+            "--days" => {
+                options.days_per_move = args
+                    .next()
+                    .and_then(|days| days.parse::<u64>().ok())
+                    .unwrap_or(3)
+            }
+            "--host" => options.host = args.next().and_then(|port| port.parse::<u16>().ok()),
+            "--connect" => options.connect = args.next(),
+            "--web" => {
+                options.web_port = args
+                    .next()
+                    .and_then(|port| port.parse::<u16>().ok())
+                    .unwrap_or(web::DEFAULT_PORT)
+            }
+            "--960" => options.chess960 = Some(None),
+            _ if arg.starts_with("--960=") => {
+                options.chess960 = Some(Some(arg["--960=".len()..].to_string()))
+            }
+            "--variant" => options.variant = args.next(),
+            "--white-rank" => options.white_rank = args.next(),
+            "--black-rank" => options.black_rank = args.next(),
+            _ => options.minutes = arg.parse::<u64>().unwrap_or(10),
+        }
+    }
+    options
+}
+
+/// How the game is shown at this terminal, and how the people sitting at it enter moves.
+struct Terminal {
+    renderer: SharedRenderer,
+    #[cfg(feature = "tui")]
+    tui: Option<tui::Tui>,
+}
+
+impl Terminal {
+    #[cfg(feature = "tui")]
+    fn open(options: &Options, orientation: Orientation, style: Style) -> Terminal {
+        if !options.tui {
+            return Terminal {
+                renderer: render::for_stdout(options.plain, orientation, style),
+                tui: None,
+            };
+        }
+        match tui::Tui::start(orientation, style) {
+            Ok(tui) => Terminal {
+                renderer: tui.renderer(),
+                tui: Some(tui),
+            },
+            Err(error) => {
+                eprintln!("Cannot start the terminal UI: {}", error);
+                process::exit(1);
+            }
+        }
+    }
+
+    #[cfg(not(feature = "tui"))]
+    fn open(options: &Options, orientation: Orientation, style: Style) -> Terminal {
+        if options.tui {
+            eprintln!("This build has no terminal UI, rebuild it with --features tui");
+            process::exit(1);
+        }
+        Terminal {
+            renderer: render::for_stdout(options.plain, orientation, style),
+        }
+    }
+
+    fn human_player(&self) -> Box<dyn Player> {
+        #[cfg(feature = "tui")]
+        if let Some(tui) = &self.tui {
+            return Box::new(tui.player());
+        }
+        Box::new(HumanPlayer::new(Arc::clone(&self.renderer)))
+    }
+}
+
+/// `kind` is "human", "engine", "web" or the path of a file of moves to replay.
+fn create_player(
+    kind: &str,
+    colour: PieceColour,
+    terminal: &Terminal,
+    web: Option<&WebServer>,
+) -> Box<dyn Player> {
+    match (kind, web) {
+        ("human", _) => terminal.human_player(),
+        ("engine", _) => Box::new(EnginePlayer::new()),
+        ("web", Some(web)) => Box::new(web.player(colour)),
+        (path, _) => match ScriptedPlayer::from_file(Path::new(path)) {
+            Ok(player) => Box::new(player),
+            Err(error) => {
+                eprintln!("Cannot read moves from {}: {}", path, error);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+/// Sets up a game against an opponent over the network. The host plays White with the
+/// `--white` player and the client Black with the `--black` one.
+fn network_game(
+    options: &Options,
+    terminal: &Terminal,
+    web: Option<&WebServer>,
+) -> io::Result<GameManager> {
+    let renderer: &SharedRenderer = &terminal.renderer;
+    let (remote, minutes): (RemotePlayer, u64) = match (options.host, &options.connect) {
+        (Some(port), _) => {
+            let remote: RemotePlayer =
+                RemotePlayer::host(port, options.minutes, Arc::clone(renderer))?;
+            (remote, options.minutes)
+        }
+        (None, Some(address)) => RemotePlayer::connect(address, Arc::clone(renderer))?,
+        (None, None) => unreachable!(),
+    };
+    let link: RemoteLink = remote.link()?;
+    let mut game: GameManager = match remote.colour() {
+        PieceColour::Black => {
+            let white_player: Box<dyn Player> =
+                create_player(&options.white, PieceColour::White, terminal, web);
+            GameManager::new(minutes, white_player, Box::new(remote))
+        }
+        PieceColour::White => {
+            let black_player: Box<dyn Player> =
+                create_player(&options.black, PieceColour::Black, terminal, web);
+            GameManager::new(minutes, Box::new(remote), black_player)
+        }
+    };
+    link.attach(&mut game)?;
+    Ok(game)
+}
+
+/// Runs `chess svg` with the arguments after "svg", returning the files written:
+///
+/// ```text
+/// chess svg [--fen <fen> | --resume <save file> | --pgn <file>] [--out <file>]
+///           [--orientation white|black] [--arrow e2e4]... [--highlight e4]... [--no-coordinates]
+/// ```
+///
+/// A FEN or an adjourned game gives one diagram, written to `--out` (default `board.svg`). A
+/// PGN gives one diagram for every position of the game, numbered from the starting position as
+/// `<out>-000.svg`, `<out>-001.svg` and so on (default `position-000.svg`). With none of them
+/// the starting position is drawn. The squares of the last move are always highlighted.
+fn svg_command(args: &[String]) -> io::Result<Vec<String>> {
+    let mut options: SvgOptions = SvgOptions::default();
+    let (mut fen, mut resume, mut pgn, mut out): (
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+        Option<&str>,
+    ) = (None, None, None, None);
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--fen" => fen = args.next(),
+            "--resume" => resume = args.next(),
+            "--pgn" => pgn = args.next(),
+            "--out" => out = args.next(),
+            "--no-coordinates" => options.coordinates = false,
+            "--orientation" => {
+                options.bottom = match args.next() {
+                    Some("black") => PieceColour::Black,
+                    _ => PieceColour::White,
+                }
+            }
+            "--arrow" => {
+                let arrow: CoordinateMove = args
+                    .next()
+                    .and_then(|notation| CoordinateMove::from_notation(notation).ok())
+                    .ok_or_else(|| error::invalid("an arrow is written as e2e4"))?;
+                options.arrows.push((arrow.from, arrow.to));
+            }
+            "--highlight" => {
+                let square: Position = args
+                    .next()
+                    .and_then(|square| Position::from_notation(square).ok())
+                    .ok_or_else(|| error::invalid("a highlight is a square such as e4"))?;
+                options.highlights.push(square);
+            }
+            _ => return Err(error::invalid(&format!("unknown option {}", arg))),
+        }
+    }
+
+    let boards: Vec<Board> = match (fen, resume, pgn) {
+        (Some(fen), _, _) => vec![
+            Board::from_fen(fen)
+                .map_err(|_| error::invalid("bad FEN"))?
+                .0,
+        ],
+        (None, Some(path), _) => vec![SavedGame::read(Path::new(path))?.replay()?.board],
+        (None, None, Some(path)) => {
+            let game: PgnGame = pgn::read_pgn(&fs::read_to_string(path)?)
+                .map_err(|_| error::invalid("the game has a move that is not legal"))?;
+            game.positions()
+                .into_iter()
+                .map(|(board, _)| board)
+                .collect()
+        }
+        (None, None, None) => vec![Board::new()],
+    };
+
+    let numbered: bool = fen.is_none() && resume.is_none() && pgn.is_some();
+    let mut written: Vec<String> = Vec::new();
+    for (i, board) in boards.iter().enumerate() {
+        let path: String = if numbered {
+            let prefix: &str = out.unwrap_or("position");
+            format!("{}-{:03}.svg", prefix.trim_end_matches(".svg"), i)
+        } else {
+            out.unwrap_or("board.svg").to_string()
+        };
+        fs::write(
+            &path,
+            svg::to_svg(board, &svg::with_last_move(board, &options)),
+        )?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Runs `chess gif` with the arguments after "gif", returning the file written:
+///
+/// ```text
+/// chess gif [--pgn <file> | --resume <save file>] [--out <file>] [--delay <ms>]
+///           [--size <pixels>] [--theme brown|green|blue|grey] [--orientation white|black]
+/// ```
+///
+/// The defaults are `replay.gif`, a second per move, a 320 pixel board and the brown theme.
+fn gif_command(args: &[String]) -> io::Result<String> {
+    let mut options: GifOptions = GifOptions::default();
+    let (mut pgn, mut resume, mut out): (Option<&str>, Option<&str>, &str) =
+        (None, None, "replay.gif");
+    let mut args = args.iter().map(String::as_str);
+    while let Some(arg) = args.next() {
+        match arg {
+            "--pgn" => pgn = args.next(),
+            "--resume" => resume = args.next(),
+            "--out" => out = args.next().unwrap_or(out),
+            "--delay" => {
+                options.delay = args
+                    .next()
+                    .and_then(|delay| delay.parse::<u32>().ok())
+                    .ok_or_else(|| error::invalid("the delay is a number of milliseconds"))?
+            }
+            "--size" => {
+                options.size = args
+                    .next()
+                    .and_then(|size| size.parse::<u16>().ok())
+                    .filter(|size| *size >= 8)
+                    .ok_or_else(|| error::invalid("the size is a number of pixels"))?
+            }
+            "--theme" => {
+                options.theme = args
+                    .next()
+                    .and_then(Theme::from_name)
+                    .ok_or_else(|| error::invalid("unknown theme"))?
+            }
+            "--orientation" => {
+                options.bottom = match args.next() {
+                    Some("black") => PieceColour::Black,
+                    _ => PieceColour::White,
+                }
+            }
+            _ => return Err(error::invalid(&format!("unknown option {}", arg))),
+        }
+    }
+
+    let game: PgnGame = match (pgn, resume) {
+        (Some(path), _) => pgn::read_pgn(&fs::read_to_string(path)?)
+            .map_err(|_| error::invalid("the game has a move that is not legal"))?,
+        (None, Some(path)) => {
+            let saved: SavedGame = SavedGame::read(Path::new(path))?;
+            // checks the moves by the saved variant's rules, which the start board then plays by
+            saved.replay()?;
+            let (start, start_turn): (Board, PieceColour) = saved
+                .new_variant()?
+                .read_fen(&saved.start)
+                .map_err(|_| error::invalid("bad start position"))?;
+            PgnGame {
+                start,
+                start_turn,
+                moves: saved
+                    .moves
+                    .iter()
+                    .map(|(coordinate_move, _)| *coordinate_move)
+                    .collect(),
+            }
+        }
+        (None, None) => return Err(error::invalid("give a game with --pgn or --resume")),
+    };
+    let boards: Vec<Board> = game
+        .positions()
+        .into_iter()
+        .map(|(board, _)| board)
+        .collect();
+    fs::write(out, gif::to_gif(&boards, &options))?;
+    Ok(out.to_string())
+}
 
 fn main() {
-    let game_duration: u64;
-    let args: Vec<String> = args().collect();
-    if args.len() < 2 {
-        game_duration = 10;
+    let arguments: Vec<String> = args().skip(1).collect();
+    if arguments.first().map(String::as_str) == Some("svg") {
+        match svg_command(&arguments[1..]) {
+            Ok(written) => {
+                for path in written {
+                    println!("Wrote {}", path);
+                }
+            }
+            Err(error) => {
+                eprintln!("Cannot export the diagram: {}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if arguments.first().map(String::as_str) == Some("gif") {
+        match gif_command(&arguments[1..]) {
+            Ok(path) => println!("Wrote {}", path),
+            Err(error) => {
+                eprintln!("Cannot make the replay: {}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    let options: Options = parse_args();
+
+    let style: Style =
+        match Style::from_options(options.pieces.as_deref(), options.squares.as_deref()) {
+            Ok(style) => style,
+            Err(error) => {
+                eprintln!("Cannot use the chosen pieces or squares: {}", error);
+                process::exit(1);
+            }
+        };
+
+    if options.xboard {
+        let mut session: XBoardSession = XBoardSession::new(options.minutes);
+        session
+            .run(io::stdin().lock(), &mut io::stdout().lock())
+            .unwrap();
+        return;
+    }
+
+    if let Some(path) = &options.correspondence {
+        if let Err(error) = correspondence::play_turn(Path::new(path), options.days_per_move, style)
+        {
+            eprintln!("Cannot play {}: {}", path, error);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let start: Option<(Board, String)> = match starting_position(&options) {
+        Ok(start) => start,
+        Err(error) => {
+            eprintln!("Cannot set up the starting position: {}", error);
+            process::exit(1);
+        }
+    };
+    let variant: Option<Box<dyn Variant>> = options.variant.as_ref().map(|name| {
+        variant::from_name(name).unwrap_or_else(|| {
+            eprintln!(
+                "Cannot play {}: the variants are king-of-the-hill, three-check, crazyhouse and atomic",
+                name
+            );
+            process::exit(1);
+        })
+    });
+    if (start.is_some() || variant.is_some())
+        && (options.host.is_some() || options.connect.is_some() || options.resume.is_some())
+    {
+        eprintln!(
+            "Cannot play a variant or a custom start over the network or when resuming a game"
+        );
+        process::exit(1);
+    }
+
+    // whoever plays Black at this terminal sees the board from their side by default
+    let orientation: Orientation = options.orientation.unwrap_or(
+        if options.connect.is_some() || (options.black == "human" && options.white != "human") {
+            Orientation::Black
+        } else {
+            Orientation::White
+        },
+    );
+    let terminal: Terminal = Terminal::open(&options, orientation, style);
+    let renderer: SharedRenderer = Arc::clone(&terminal.renderer);
+    let web: Option<WebServer> = if options.white == "web" || options.black == "web" {
+        match WebServer::start(options.web_port) {
+            Ok(web) => {
+                renderer.lock().unwrap().line(
+                    Line::Status,
+                    &format!(
+                        "Open http://localhost:{}/ to play in the browser",
+                        options.web_port
+                    ),
+                );
+                Some(web)
+            }
+            Err(error) => {
+                eprintln!("Cannot serve on port {}: {}", options.web_port, error);
+                process::exit(1);
+            }
+        }
     } else {
-        game_duration = match args.get(1).unwrap().parse::<u64>() {
-            Ok(ammount) => ammount,
-            Err(_) => 10,
+        None
+    };
+
+    let mut game: GameManager = if options.host.is_some() || options.connect.is_some() {
+        match network_game(&options, &terminal, web.as_ref()) {
+            Ok(game) => game,
+            Err(error) => {
+                eprintln!("Cannot start the network game: {}", error);
+                process::exit(1);
+            }
+        }
+    } else {
+        local_game(&options, start, &terminal, web.as_ref())
+    };
+    if let Some(variant) = variant {
+        game.set_variant(variant);
+    }
+    game.set_renderer(Arc::clone(&renderer));
+    if let Some(web) = &web {
+        if let Err(error) = web.attach(&mut game) {
+            eprintln!("Cannot show the game in the browser: {}", error);
+            process::exit(1);
+        }
+    }
+    let result: GameResult = game.start_game();
+    renderer.lock().unwrap().close();
+
+    if let Some(path) = options.pgn {
+        if let Err(error) = fs::write(&path, game.pgn()) {
+            eprintln!("Cannot write {}: {}", path, error);
+        }
+    }
+    if let Some(web) = &web {
+        web.linger();
+    }
+    process::exit(result.exit_code());
+}
+
+fn local_game(
+    options: &Options,
+    start: Option<(Board, String)>,
+    terminal: &Terminal,
+    web: Option<&WebServer>,
+) -> GameManager {
+    let white_player: Box<dyn Player> =
+        create_player(&options.white, PieceColour::White, terminal, web);
+    let black_player: Box<dyn Player> =
+        create_player(&options.black, PieceColour::Black, terminal, web);
+    match &options.resume {
+        Some(path) => {
+            match SavedGame::read(Path::new(path))
+                .and_then(|saved| GameManager::resume(&saved, white_player, black_player))
+            {
+                Ok(game) => game,
+                Err(error) => {
+                    eprintln!("Cannot resume {}: {}", path, error);
+                    process::exit(1);
+                }
+            }
+        }
+        None => match start {
+            Some((board, description)) => {
+                terminal
+                    .renderer
+                    .lock()
+                    .unwrap()
+                    .line(Line::Status, &description);
+                GameManager::from_position(
+                    options.minutes,
+                    board,
+                    PieceColour::White,
+                    white_player,
+                    black_player,
+                )
+            }
+            None => GameManager::new(options.minutes, white_player, black_player),
+        },
+    }
+}
+
+/// The board chosen with `--960`, `--white-rank` or `--black-rank`, and a line telling the
+/// players which position it is; `None` for the standard start.
+fn starting_position(options: &Options) -> io::Result<Option<(Board, String)>> {
+    match (&options.chess960, &options.white_rank, &options.black_rank) {
+        (None, None, None) => Ok(None),
+        (Some(_), Some(_), _) | (Some(_), _, Some(_)) => Err(error::invalid(
+            "--960 cannot be combined with --white-rank or --black-rank",
+        )),
+        (Some(Some(index)), None, None) if index == "double" => {
+            let (white, black, board) = chess960::double_start_position();
+            let description: String =
+                format!("Chess960 positions {} (White) and {} (Black)", white, black);
+            Ok(Some((board, description)))
+        }
+        (Some(index), None, None) => {
+            let index: u16 = match index {
+                Some(index) => index
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|&index| index < chess960::POSITION_COUNT)
+                    .ok_or_else(|| {
+                        error::invalid(&format!(
+                            "no Chess960 position {}: use 0 to 959 or double",
+                            index
+                        ))
+                    })?,
+                None => chess960::random_index(),
+            };
+            let board: Board = chess960::start_position(index).unwrap();
+            Ok(Some((board, format!("Chess960 position {}", index))))
+        }
+        (None, white, black) => {
+            let back_rank = |text: &Option<String>| match text {
+                Some(text) => chess960::parse_back_rank(text),
+                None => Ok(STANDARD_BACK_RANK),
+            };
+            let board: Board = Board::with_back_ranks(back_rank(white)?, back_rank(black)?);
+            Ok(Some((board, String::from("Custom starting position"))))
         }
     }
-    let mut game: GameManager = GameManager::new(game_duration);
-    game.start_game();
 }
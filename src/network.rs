@@ -0,0 +1,338 @@
+//! Two-player games over TCP. Each player runs the program, one with `--host <port>` and the
+//! other with `--connect <address>`, and the opponent on the far end becomes a `RemotePlayer`.
+//!
+//! The protocol is line based. On connecting the host sends `chess <minutes>`; the host plays
+//! White and the client Black. After that either side may send:
+//!
+//! ```text
+//! move e7e8q        a move in coordinate notation
+//! move e2e4 draw    a move together with a draw offer
+//! accept            accepts the pending draw offer
+//! resign
+//! adjourn           the game was saved at that end and stops without a result
+//! ```
+//!
+//! and the host also sends `clock <white ms> <black ms>` after every move and `flag <colour>`
+//! when a clock runs out. Both ends play every move on their own board, so an illegal move
+//! means the two programs disagree about the game and ends it. The host's clocks are
+//! authoritative: the client's only keep the display running between updates.
+
+use crate::{
+    board::Board,
+    error,
+    game::{Flag, GameManager},
+    piece::{PieceColour, PieceType},
+    player::{Input, Interrupt, Notification, Player, PlayerAction},
+    position::CoordinateMove,
+    render::{Line, SharedRenderer},
+    result::Termination,
+    timer::Timer,
+};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+type Clocks = Arc<OnceLock<(Arc<Timer>, Arc<Timer>)>>;
+type RemoteFlag = Arc<OnceLock<Flag>>;
+
+/// The opponent at the other end of the connection.
+pub struct RemotePlayer {
+    link: RemoteLink,
+    colour: PieceColour,
+    lines: Receiver<String>,
+    interrupt: Interrupt,
+    promotion: PieceType,
+    accepted_draw: bool,
+    adjourned: bool,
+    /// The other program sent something that makes no sense here, so the game cannot go on.
+    desynced: bool,
+    renderer: SharedRenderer,
+}
+
+/// Lets the game send clock updates and flag falls to the other end.
+pub struct RemoteLink {
+    writer: TcpStream,
+    clocks: Clocks,
+    flag: RemoteFlag,
+    host: bool,
+}
+
+impl RemotePlayer {
+    /// Waits for an opponent to connect on `port` and sends them the time control.
+    pub fn host(port: u16, minutes: u64, renderer: SharedRenderer) -> io::Result<Self> {
+        let listener: TcpListener = TcpListener::bind(("0.0.0.0", port))?;
+        renderer.lock().unwrap().line(
+            Line::Status,
+            &format!("Waiting for an opponent on port {}", port),
+        );
+        let (mut stream, address) = listener.accept()?;
+        renderer
+            .lock()
+            .unwrap()
+            .line(Line::Status, &format!("{} connected", address));
+        writeln!(stream, "chess {}", minutes)?;
+        let reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+        RemotePlayer::start(stream, reader, true, renderer)
+    }
+
+    /// Connects to a host, returning the opponent and the minutes each side starts with.
+    pub fn connect(address: &str, renderer: SharedRenderer) -> io::Result<(Self, u64)> {
+        let stream: TcpStream = TcpStream::connect(address)?;
+        let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+        let mut greeting: String = String::new();
+        reader.read_line(&mut greeting)?;
+        let minutes: u64 = greeting
+            .trim()
+            .strip_prefix("chess ")
+            .and_then(|minutes| minutes.parse().ok())
+            .ok_or_else(|| error::invalid("not a chess host"))?;
+        Ok((
+            RemotePlayer::start(stream, reader, false, renderer)?,
+            minutes,
+        ))
+    }
+
+    fn start(
+        stream: TcpStream,
+        reader: BufReader<TcpStream>,
+        host: bool,
+        renderer: SharedRenderer,
+    ) -> io::Result<Self> {
+        let clocks: Clocks = Arc::new(OnceLock::new());
+        let flag: RemoteFlag = Arc::new(OnceLock::new());
+        let (sender, lines): (Sender<String>, Receiver<String>) = mpsc::channel();
+        let reader_clocks: Clocks = Arc::clone(&clocks);
+        let reader_flag: RemoteFlag = Arc::clone(&flag);
+        let reader_renderer: SharedRenderer = Arc::clone(&renderer);
+        thread::spawn(move || {
+            RemotePlayer::read_lines(reader, sender, reader_clocks, reader_flag, reader_renderer)
+        });
+
+        Ok(RemotePlayer {
+            link: RemoteLink {
+                writer: stream,
+                clocks,
+                flag,
+                host,
+            },
+            colour: if host {
+                PieceColour::Black
+            } else {
+                PieceColour::White
+            },
+            lines,
+            interrupt: Interrupt::default(),
+            promotion: PieceType::Queen,
+            accepted_draw: false,
+            adjourned: false,
+            desynced: false,
+            renderer,
+        })
+    }
+
+    pub fn colour(&self) -> PieceColour {
+        self.colour
+    }
+
+    pub fn link(&self) -> io::Result<RemoteLink> {
+        Ok(RemoteLink {
+            writer: self.link.writer.try_clone()?,
+            clocks: Arc::clone(&self.link.clocks),
+            flag: Arc::clone(&self.link.flag),
+            host: self.link.host,
+        })
+    }
+
+    /// Handles clock updates and flag falls as soon as they arrive and passes every other line
+    /// on to `request_move`.
+    fn read_lines(
+        reader: BufReader<TcpStream>,
+        sender: Sender<String>,
+        clocks: Clocks,
+        flag: RemoteFlag,
+        renderer: SharedRenderer,
+    ) {
+        for line in reader.lines() {
+            let line: String = match line {
+                Ok(line) => line,
+                Err(_) => break,
+            };
+            let mut words = line.split_whitespace();
+            match words.next() {
+                Some("clock") => {
+                    let times: Vec<u64> = words.filter_map(|time| time.parse().ok()).collect();
+                    if let (Some((white_timer, black_timer)), [white, black]) =
+                        (clocks.get(), times.as_slice())
+                    {
+                        white_timer.set_remaining(Duration::from_millis(*white));
+                        black_timer.set_remaining(Duration::from_millis(*black));
+                    }
+                }
+                Some("flag") => {
+                    let (loser, name): (PieceColour, &str) = match words.next() {
+                        Some("white") => (PieceColour::White, "White"),
+                        _ => (PieceColour::Black, "Black"),
+                    };
+                    renderer
+                        .lock()
+                        .unwrap()
+                        .line(Line::Status, &format!("{} ran out of time", name));
+                    if let Some(flag) = flag.get() {
+                        flag.fall(loser);
+                    }
+                }
+                _ => {
+                    if sender.send(line).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    /// The two programs no longer agree about the game, so it cannot go on: the opponent is
+    /// taken to have abandoned it.
+    fn desync(&mut self) -> PlayerAction {
+        self.desynced = true;
+        self.show(
+            Line::Status,
+            "The opponent's program sent a move that is not legal here, ending the game",
+        );
+        PlayerAction::Abandon
+    }
+
+    fn show(&self, line: Line, text: &str) {
+        self.renderer.lock().unwrap().line(line, text);
+    }
+}
+
+impl Player for RemotePlayer {
+    fn request_move(&mut self, _board: &Board, _colour: PieceColour) -> PlayerAction {
+        if self.desynced {
+            return PlayerAction::Abandon;
+        }
+        self.show(Line::Prompt, "waiting for the opponent...");
+
+        let line: String = match self.interrupt.recv(&self.lines) {
+            Input::Received(line) => line,
+            Input::Closed => {
+                self.show(Line::Status, "The connection to the opponent was lost");
+                return PlayerAction::Abandon;
+            }
+            Input::Interrupted => return PlayerAction::Interrupted,
+        };
+        self.link.send_clocks();
+
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("move"), Some(notation), offer) => {
+                if let Ok(CoordinateMove {
+                    promotion: Some(promotion),
+                    ..
+                }) = CoordinateMove::from_notation(notation)
+                {
+                    self.promotion = promotion;
+                }
+                PlayerAction::Move {
+                    notation: notation.to_string(),
+                    draw_offer: offer == Some("draw"),
+                }
+            }
+            (Some("accept"), None, None) => {
+                self.accepted_draw = true;
+                PlayerAction::AcceptDraw
+            }
+            (Some("resign"), None, None) => PlayerAction::Resign,
+            (Some("adjourn"), None, None) => {
+                self.adjourned = true;
+                self.show(Line::Status, "The opponent adjourned the game");
+                PlayerAction::Adjourn
+            }
+            _ => self.desync(),
+        }
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        self.promotion
+    }
+
+    fn notify(&mut self, notification: &Notification) {
+        match notification {
+            Notification::OpponentMoved(record) => {
+                let mut line: String = format!("move {}", record.coordinate_move.to_notation());
+                if record.draw_offer {
+                    line.push_str(" draw");
+                }
+                self.link.send(&line);
+                self.link.send_clocks();
+            }
+            Notification::GameOver {
+                winner,
+                termination,
+            } => {
+                if *termination == Termination::Resignation && *winner == Some(self.colour) {
+                    self.link.send("resign");
+                } else if *termination == Termination::Agreement && !self.accepted_draw {
+                    self.link.send("accept");
+                } else if *termination == Termination::Adjourned && !self.adjourned {
+                    self.link.send("adjourn");
+                }
+            }
+            // the game asks for a move again, and is told the opponent has gone
+            Notification::MoveRejected(_)
+            | Notification::InvalidNotation
+            | Notification::NoDrawOffer => {
+                self.desync();
+            }
+            _ => (),
+        }
+    }
+
+    fn interrupt(&self) -> Option<Interrupt> {
+        Some(self.interrupt.clone())
+    }
+}
+
+impl RemoteLink {
+    /// Shares the game's clocks with the connection. The host tells the client when a flag
+    /// falls, while the client leaves that to the host.
+    pub fn attach(&self, game: &mut GameManager) -> io::Result<()> {
+        let _ = self.clocks.set(game.clocks());
+        let _ = self.flag.set(game.flag());
+        if self.host {
+            let mut writer: TcpStream = self.writer.try_clone()?;
+            game.on_flag(Box::new(move |colour| {
+                let colour: &str = match colour {
+                    PieceColour::White => "white",
+                    PieceColour::Black => "black",
+                };
+                let _ = writeln!(writer, "flag {}", colour);
+            }));
+        } else {
+            game.follow_remote_clocks();
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, line: &str) {
+        // a lost connection shows up as end of input on the reading side
+        let _ = writeln!(self.writer, "{}", line);
+    }
+
+    fn send_clocks(&mut self) {
+        if !self.host {
+            return;
+        }
+        if let Some((white_timer, black_timer)) = self.clocks.get() {
+            let line: String = format!(
+                "clock {} {}",
+                white_timer.remaining_duration().as_millis(),
+                black_timer.remaining_duration().as_millis()
+            );
+            self.send(&line);
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{
+    board::Board,
+    piece::PieceColour,
+    position::{CoordinateMove, MoveResult, NotationError},
+    variant::{self, Standard, Variant},
+};
+
+const LINE_LENGTH: usize = 79;
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MoveRecord {
+    pub coordinate_move: CoordinateMove,
+    pub san: String,
+    pub draw_offer: bool,
+}
+
+/// Writes a game in Portable Game Notation. Draw offers follow their move as an "=" annotation,
+/// as in `12. Nf3 =`.
+pub fn to_pgn(moves: &[MoveRecord], result: &str) -> String {
+    to_pgn_from(
+        &Standard,
+        &Board::new().to_fen(PieceColour::White, 1),
+        moves,
+        result,
+    )
+}
+
+/// Writes a game of `variant` that started from `start_fen`, in the variant's FEN. Games not
+/// starting from the standard position get `SetUp` and `FEN` tags, and variant and Chess960
+/// games a `Variant` tag as well.
+pub fn to_pgn_from(
+    variant: &dyn Variant,
+    start_fen: &str,
+    moves: &[MoveRecord],
+    result: &str,
+) -> String {
+    let mut tags: Vec<(&str, &str)> = vec![
+        ("Event", "Casual game"),
+        ("Site", "?"),
+        ("Date", "????.??.??"),
+        ("Round", "-"),
+        ("White", "?"),
+        ("Black", "?"),
+        ("Result", result),
+    ];
+    if !variant.is_standard() {
+        tags.push(("Variant", variant.name()));
+    }
+    let mut standard: Board = Board::new();
+    variant.set_up(&mut standard);
+    if start_fen != variant.to_fen(&standard, PieceColour::White, 1) {
+        if variant.is_standard()
+            && Board::from_fen(start_fen).is_ok_and(|(board, _)| board.is_chess960())
+        {
+            tags.push(("Variant", "Chess960"));
+        }
+        tags.push(("SetUp", "1"));
+        tags.push(("FEN", start_fen));
+    }
+
+    let mut pgn: String = String::new();
+    for (tag, value) in tags {
+        pgn.push_str(&format!("[{} \"{}\"]\n", tag, value));
+    }
+    pgn.push('\n');
+
+    let mut tokens: Vec<String> = Vec::new();
+    for (i, record) in moves.iter().enumerate() {
+        if i % 2 == 0 {
+            tokens.push(format!("{}.", i / 2 + 1));
+        }
+        tokens.push(record.san.clone());
+        if record.draw_offer {
+            tokens.push(String::from("="));
+        }
+    }
+    tokens.push(result.to_string());
+
+    let mut line_length: usize = 0;
+    for token in tokens {
+        if line_length > 0 && line_length + 1 + token.len() > LINE_LENGTH {
+            pgn.push('\n');
+            line_length = 0;
+        } else if line_length > 0 {
+            pgn.push(' ');
+            line_length += 1;
+        }
+        line_length += token.len();
+        pgn.push_str(&token);
+    }
+    pgn.push('\n');
+    pgn
+}
+
+/// A game read from PGN: where it started and every move played from there.
+pub struct PgnGame {
+    pub start: Board,
+    pub start_turn: PieceColour,
+    pub moves: Vec<CoordinateMove>,
+}
+
+impl PgnGame {
+    /// The position after each move, starting with the position before the first.
+    pub fn positions(&self) -> Vec<(Board, PieceColour)> {
+        let mut board: Board = self.start.clone();
+        let mut turn: PieceColour = self.start_turn;
+        let mut positions: Vec<(Board, PieceColour)> = vec![(board.clone(), turn)];
+        for coordinate_move in &self.moves {
+            board.play_coordinate_move(turn, coordinate_move);
+            turn = turn.opponent();
+            positions.push((board.clone(), turn));
+        }
+        positions
+    }
+}
+
+/// Reads the first game of a PGN file, starting from its `FEN` tag if it has one. Comments,
+/// variations and annotations are skipped, and each move is matched against the legal moves
+/// so any correct SAN is understood.
+pub fn read_pgn(text: &str) -> Result<PgnGame, NotationError> {
+    let mut fen: Option<&str> = None;
+    let mut variant: Box<dyn Variant> = Box::new(Standard);
+    let mut movetext: String = String::new();
+    let mut in_movetext: bool = false;
+    for line in text.lines() {
+        let line: &str = line.trim();
+        if line.starts_with('[') && !in_movetext {
+            let tag_value = |tag: &str| {
+                line.strip_prefix(&format!("[{} \"", tag))
+                    .and_then(|rest| rest.strip_suffix("\"]"))
+            };
+            if let Some(value) = tag_value("FEN") {
+                fen = Some(value);
+            }
+            // Chess960 and other names without their own rules play as standard chess
+            if let Some(found) = tag_value("Variant").and_then(variant::from_name) {
+                variant = found;
+            }
+            continue;
+        }
+        if line.is_empty() && in_movetext {
+            // a blank line after the moves ends the game
+            break;
+        }
+        in_movetext |= !line.is_empty();
+        // a semicolon comments out the rest of the line
+        movetext.push_str(line.split(';').next().unwrap_or_default());
+        movetext.push(' ');
+    }
+
+    let (start, start_turn): (Board, PieceColour) = match fen {
+        Some(fen) => variant.read_fen(fen)?,
+        None => {
+            let mut board: Board = Board::new();
+            variant.set_up(&mut board);
+            (board, PieceColour::White)
+        }
+    };
+    let mut board: Board = start.clone();
+    let mut turn: PieceColour = start_turn;
+    let mut moves: Vec<CoordinateMove> = Vec::new();
+    for token in movetext_tokens(&movetext) {
+        let coordinate_move: CoordinateMove = find_san(&board, turn, &token)?;
+        match board.play_coordinate_move(turn, &coordinate_move) {
+            MoveResult::Success | MoveResult::Draw => (),
+            _ => return Err(NotationError),
+        }
+        moves.push(coordinate_move);
+        turn = turn.opponent();
+    }
+    Ok(PgnGame {
+        start,
+        start_turn,
+        moves,
+    })
+}
+
+/// The moves of the main line, without move numbers, comments, variations, annotations or the
+/// result.
+fn movetext_tokens(movetext: &str) -> Vec<String> {
+    let mut tokens: Vec<String> = Vec::new();
+    let mut comment: bool = false;
+    let mut variation_depth: usize = 0;
+    let mut token: String = String::new();
+    for character in movetext.chars().chain(std::iter::once(' ')) {
+        match character {
+            '{' => comment = true,
+            '}' => comment = false,
+            _ if comment => (),
+            '(' => variation_depth += 1,
+            ')' => variation_depth = variation_depth.saturating_sub(1),
+            _ if variation_depth > 0 => (),
+            ' ' | '\t' => {
+                let word: &str = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+                let is_result: bool = matches!(token.as_str(), "1-0" | "0-1" | "1/2-1/2" | "*");
+                // a lone "=" offers a draw with the move before it
+                let is_draw_offer: bool = word == "=";
+                if !word.is_empty() && !word.starts_with('$') && !is_result && !is_draw_offer {
+                    tokens.push(word.to_string());
+                }
+                token.clear();
+            }
+            _ => token.push(character),
+        }
+    }
+    tokens
+}
+
+/// The legal move written as `san`, ignoring check marks and annotations such as "!?".
+fn find_san(board: &Board, turn: PieceColour, san: &str) -> Result<CoordinateMove, NotationError> {
+    // a pawn drop may leave out its letter, as in "@e4"
+    let bare = |san: &str| -> String {
+        let san: &str = san.trim_end_matches(['+', '#', '!', '?']);
+        match san.strip_prefix('@') {
+            Some(square) => format!("P@{}", square),
+            None => san.replace('0', "O"),
+        }
+    };
+    let wanted: String = bare(san);
+    board
+        .clone()
+        .legal_moves(turn)
+        .into_iter()
+        .find(|coordinate_move| bare(&board.san(turn, coordinate_move)) == wanted)
+        .ok_or(NotationError)
+}
@@ -1,6 +1,7 @@
 use crate::position::Position;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PieceType {
     King,
     Queen,
@@ -9,18 +10,69 @@ pub enum PieceType {
     Knight,
     Pawn,
 }
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PieceColour {
     Black,
     White,
 }
 
+impl PieceType {
+    pub fn value(&self) -> i32 {
+        match self {
+            PieceType::King => 0,
+            PieceType::Queen => 9,
+            PieceType::Rook => 5,
+            PieceType::Bishop => 3,
+            PieceType::Knight => 3,
+            PieceType::Pawn => 1,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            PieceType::King => 'K',
+            PieceType::Queen => 'Q',
+            PieceType::Rook => 'R',
+            PieceType::Bishop => 'B',
+            PieceType::Knight => 'N',
+            PieceType::Pawn => 'P',
+        }
+    }
+
+    pub fn from_letter(letter: char) -> Option<PieceType> {
+        match letter.to_ascii_uppercase() {
+            'K' => Some(PieceType::King),
+            'Q' => Some(PieceType::Queen),
+            'R' => Some(PieceType::Rook),
+            'B' => Some(PieceType::Bishop),
+            'N' => Some(PieceType::Knight),
+            'P' => Some(PieceType::Pawn),
+            _ => None,
+        }
+    }
+}
+
+impl PieceColour {
+    pub fn opponent(&self) -> PieceColour {
+        match self {
+            PieceColour::Black => PieceColour::White,
+            PieceColour::White => PieceColour::Black,
+        }
+    }
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Piece {
     pub piece_type: PieceType,
     pub position: Position,
-    pub symbol: char,
     pub colour: PieceColour,
     pub special: bool,
+    /// Whether the piece is a promoted pawn, which goes back to the pocket as a pawn when it is
+    /// taken in Crazyhouse.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub promoted: bool,
 }
 
 impl Piece {
@@ -31,31 +83,12 @@ impl Piece {
         colour: PieceColour,
         special: bool,
     ) -> Piece {
-        let mut symbol: char = match (piece_type, colour) {
-            (PieceType::King, PieceColour::Black) => '♔',
-            (PieceType::Bishop, PieceColour::Black) => '♗',
-            (PieceType::Knight, PieceColour::Black) => '♘',
-            (PieceType::Pawn, PieceColour::Black) => '♙',
-            (PieceType::Queen, PieceColour::Black) => '♕',
-            (PieceType::Rook, PieceColour::Black) => '♖',
-            (PieceType::King, PieceColour::White) => '♚',
-            (PieceType::Bishop, PieceColour::White) => '♝',
-            (PieceType::Knight, PieceColour::White) => '♞',
-            (PieceType::Pawn, PieceColour::White) => '♟',
-            (PieceType::Queen, PieceColour::White) => '♛',
-            (PieceType::Rook, PieceColour::White) => '♜',
-        };
-
-        if let PieceColour::Black = colour {
-            symbol = symbol.to_lowercase().next().unwrap();
-        }
-
         Piece {
             piece_type,
             colour,
             position: Position::new(row, column),
-            symbol,
             special,
+            promoted: false,
         }
     }
 }
@@ -0,0 +1,423 @@
+use crate::{
+    board::Board,
+    engine,
+    pgn::MoveRecord,
+    piece::{PieceColour, PieceType},
+    position::{CoordinateMove, MoveResult, Position},
+    render::{Line, SharedRenderer},
+    result::Termination,
+};
+use std::fs;
+use std::io::{self, BufRead};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+const DEFAULT_SAVE_FILE: &str = "chess.save";
+const DEFAULT_SVG_FILE: &str = "board.svg";
+/// How often a player waiting for input looks out for an [`Interrupt`].
+const INTERRUPT_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+pub enum PlayerAction {
+    Move {
+        notation: String,
+        draw_offer: bool,
+    },
+    AcceptDraw,
+    Resign,
+    /// The player is gone, such as a network opponent whose connection dropped.
+    Abandon,
+    /// Ends the game without a result, such as a network opponent who adjourned it at their end.
+    Adjourn,
+    /// The player stopped waiting for input because their [`Interrupt`] was raised.
+    Interrupted,
+    Save(String),
+    ShowMoves(Position),
+    /// Draws the current position to an SVG file.
+    ExportSvg(String),
+}
+
+impl PlayerAction {
+    /// Parses a line such as "Nf3", "Nf3 draw" or "Nf3 (=)" (the move plus a draw offer),
+    /// "accept", "resign", "save [file]", "svg [file]" or `moves <square>`.
+    pub fn parse(line: &str) -> PlayerAction {
+        let line: &str = line.trim();
+        if line.eq_ignore_ascii_case("save") {
+            return PlayerAction::Save(String::from(DEFAULT_SAVE_FILE));
+        }
+        if let Some(path) = strip_command(line, "save ") {
+            return PlayerAction::Save(path.trim().to_string());
+        }
+        if line.eq_ignore_ascii_case("svg") {
+            return PlayerAction::ExportSvg(String::from(DEFAULT_SVG_FILE));
+        }
+        if let Some(path) = strip_command(line, "svg ") {
+            return PlayerAction::ExportSvg(path.trim().to_string());
+        }
+        if let Some(square) = strip_command(line, "moves ") {
+            if let Ok(position) = Position::from_notation(square) {
+                return PlayerAction::ShowMoves(position);
+            }
+        }
+        if line.eq_ignore_ascii_case("resign") {
+            return PlayerAction::Resign;
+        }
+        if line.eq_ignore_ascii_case("accept") {
+            return PlayerAction::AcceptDraw;
+        }
+
+        // "draw" needs whitespace before it, so that it cannot run into the move
+        let offered: Option<&str> = strip_suffix(line, "draw")
+            .filter(|notation| notation.ends_with(char::is_whitespace))
+            .or_else(|| line.strip_suffix("(=)"));
+        if let Some(notation) = offered {
+            if !notation.trim().is_empty() {
+                return PlayerAction::Move {
+                    notation: notation.trim().to_string(),
+                    draw_offer: true,
+                };
+            }
+        }
+
+        PlayerAction::Move {
+            notation: line.to_string(),
+            draw_offer: false,
+        }
+    }
+}
+
+/// The rest of `line` after `command`, which is matched in any case like the bare commands, so
+/// "SAVE game.txt" saves too.
+fn strip_command<'a>(line: &'a str, command: &str) -> Option<&'a str> {
+    let start: &str = line.get(..command.len())?;
+    start
+        .eq_ignore_ascii_case(command)
+        .then(|| &line[command.len()..])
+}
+
+/// `line` without `suffix`, which is matched in any case like `strip_command`.
+fn strip_suffix<'a>(line: &'a str, suffix: &str) -> Option<&'a str> {
+    let start: usize = line.len().checked_sub(suffix.len())?;
+    line.get(start..)?
+        .eq_ignore_ascii_case(suffix)
+        .then(|| &line[..start])
+}
+
+pub enum Notification {
+    MoveAccepted(MoveRecord),
+    OpponentMoved(MoveRecord),
+    DrawOffered,
+    DrawDeclined,
+    NoDrawOffer,
+    MoveRejected(MoveResult),
+    InvalidNotation,
+    SaveFailed(String),
+    GameOver {
+        winner: Option<PieceColour>,
+        termination: Termination,
+    },
+}
+
+/// Explains to the player why their move was not played.
+pub fn rejection_message(move_result: MoveResult) -> &'static str {
+    match move_result {
+        MoveResult::AmbiguousMove => {
+            "Multiple pieces can make this move, consider specifying the starting row or column"
+        }
+        MoveResult::Checked => "Cannot make this move due to check",
+        MoveResult::MissingPiece => "No piece can make this move",
+        MoveResult::PiecePinned => "This piece is pinned",
+        _ => "This move is not legal",
+    }
+}
+
+/// The piece a player typed when asked what to promote to: its letter, in either case, or
+/// nothing for a queen. `None` when there is no such piece.
+pub fn promotion_choice(line: &str) -> Option<PieceType> {
+    match line.trim().to_ascii_lowercase().chars().next() {
+        Some('r') => Some(PieceType::Rook),
+        Some('q') | None => Some(PieceType::Queen),
+        Some('n') | Some('k') => Some(PieceType::Knight),
+        Some('b') => Some(PieceType::Bishop),
+        _ => None,
+    }
+}
+
+/// What a player at the terminal is told about `notification`, and where.
+#[doc(hidden)]
+pub fn notification_line(notification: &Notification) -> Option<(Line, String)> {
+    let (line, text): (Line, String) = match notification {
+        Notification::InvalidNotation => (Line::Status, String::from("Notation is invalid")),
+        Notification::MoveRejected(move_result) => {
+            (Line::Status, rejection_message(*move_result).to_string())
+        }
+        Notification::NoDrawOffer => (
+            Line::Status,
+            String::from("There is no draw offer to accept"),
+        ),
+        Notification::SaveFailed(reason) => {
+            (Line::Status, format!("Could not save the game: {}", reason))
+        }
+        Notification::OpponentMoved(record) => (
+            Line::OpponentMove,
+            format!("opponent played {}", record.san),
+        ),
+        Notification::DrawOffered => (
+            Line::DrawOffer,
+            String::from("opponent offers a draw: type accept, or play a move to decline"),
+        ),
+        Notification::DrawDeclined => (Line::DrawOffer, String::from("opponent declined the draw")),
+        Notification::MoveAccepted(_) | Notification::GameOver { .. } => return None,
+    };
+    Some((line, text))
+}
+
+/// One side of a game. `GameManager` asks the player to move when it is their turn and keeps
+/// them informed about everything else that happens.
+pub trait Player {
+    fn request_move(&mut self, board: &Board, colour: PieceColour) -> PlayerAction;
+
+    fn choose_promotion(&mut self, board: &Board, colour: PieceColour) -> PieceType;
+
+    fn notify(&mut self, notification: &Notification);
+
+    /// Lets `GameManager` stop the player waiting for input once their flag falls. Players that
+    /// answer straight away need none.
+    fn interrupt(&self) -> Option<Interrupt> {
+        None
+    }
+}
+
+/// Raised by `GameManager` when a flag falls, so that a player waiting for input gives up and
+/// the game can end.
+#[derive(Clone, Default)]
+pub struct Interrupt(Arc<AtomicBool>);
+
+/// What came of waiting for input with [`Interrupt::recv`].
+pub enum Input<T> {
+    Received(T),
+    /// Nothing more will arrive.
+    Closed,
+    Interrupted,
+}
+
+impl Interrupt {
+    pub fn raise(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_raised(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Waits for the next value on `receiver`, giving up once the interrupt is raised.
+    pub fn recv<T>(&self, receiver: &Receiver<T>) -> Input<T> {
+        loop {
+            if self.is_raised() {
+                return Input::Interrupted;
+            }
+            match receiver.recv_timeout(INTERRUPT_CHECK_INTERVAL) {
+                Ok(value) => return Input::Received(value),
+                Err(RecvTimeoutError::Disconnected) => return Input::Closed,
+                Err(RecvTimeoutError::Timeout) => (),
+            }
+        }
+    }
+}
+
+/// The lines typed into the terminal, read by one thread for the whole program so that a
+/// player who stops waiting does not leave a read behind to swallow the next line.
+fn stdin_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let (sender, receiver): (Sender<String>, Receiver<String>) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let sent: bool = match line {
+                    Ok(line) => sender.send(line).is_ok(),
+                    Err(_) => false,
+                };
+                if !sent {
+                    break;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// Reads moves typed into the terminal.
+#[doc(hidden)]
+pub struct HumanPlayer {
+    interrupt: Interrupt,
+    renderer: SharedRenderer,
+}
+
+impl HumanPlayer {
+    pub fn new(renderer: SharedRenderer) -> Self {
+        HumanPlayer {
+            interrupt: Interrupt::default(),
+            renderer,
+        }
+    }
+
+    fn next_line(&self) -> Input<String> {
+        self.interrupt.recv(&stdin_lines().lock().unwrap())
+    }
+
+    fn show(&self, line: Line, text: &str) {
+        self.renderer.lock().unwrap().line(line, text);
+    }
+}
+
+impl Player for HumanPlayer {
+    fn request_move(&mut self, _board: &Board, _colour: PieceColour) -> PlayerAction {
+        self.show(Line::Prompt, "your move:");
+        match self.next_line() {
+            Input::Received(move_notation) => PlayerAction::parse(&move_notation),
+            Input::Closed => PlayerAction::Resign,
+            Input::Interrupted => PlayerAction::Interrupted,
+        }
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        self.show(Line::Status, "choose piece to promote to:");
+        loop {
+            let line: String = match self.next_line() {
+                Input::Received(line) => line,
+                Input::Closed | Input::Interrupted => String::new(),
+            };
+            match promotion_choice(&line) {
+                Some(piece_type) => return piece_type,
+                None => self.show(Line::Status, "Invalid piece symbol"),
+            }
+        }
+    }
+
+    fn notify(&mut self, notification: &Notification) {
+        if let Some((line, text)) = notification_line(notification) {
+            self.show(line, &text);
+        }
+    }
+
+    fn interrupt(&self) -> Option<Interrupt> {
+        Some(self.interrupt.clone())
+    }
+}
+
+/// Plays the moves chosen by the built-in engine, accepting draws when behind on material.
+#[doc(hidden)]
+pub struct EnginePlayer {
+    promotion: PieceType,
+    draw_offered: bool,
+}
+
+impl Default for EnginePlayer {
+    fn default() -> Self {
+        EnginePlayer::new()
+    }
+}
+
+impl EnginePlayer {
+    pub fn new() -> Self {
+        EnginePlayer {
+            promotion: PieceType::Queen,
+            draw_offered: false,
+        }
+    }
+}
+
+impl Player for EnginePlayer {
+    fn request_move(&mut self, board: &Board, colour: PieceColour) -> PlayerAction {
+        if self.draw_offered && board.material(colour) < board.material(colour.opponent()) {
+            return PlayerAction::AcceptDraw;
+        }
+        self.draw_offered = false;
+
+        match engine::choose_move(board, colour) {
+            Some(coordinate_move) => {
+                self.promotion = coordinate_move.promotion.unwrap_or(PieceType::Queen);
+                PlayerAction::Move {
+                    notation: coordinate_move.to_notation(),
+                    draw_offer: false,
+                }
+            }
+            None => PlayerAction::Resign,
+        }
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        self.promotion
+    }
+
+    fn notify(&mut self, notification: &Notification) {
+        if let Notification::DrawOffered = notification {
+            self.draw_offered = true;
+        }
+    }
+}
+
+/// Replays a fixed list of moves and resigns once they run out.
+pub struct ScriptedPlayer {
+    moves: Vec<String>,
+    next: usize,
+    promotion: PieceType,
+}
+
+impl ScriptedPlayer {
+    pub fn new(moves: Vec<String>) -> Self {
+        ScriptedPlayer {
+            moves,
+            next: 0,
+            promotion: PieceType::Queen,
+        }
+    }
+
+    /// Reads whitespace separated moves, skipping move numbers such as "12.". A "draw", "(=)"
+    /// or "=" token offers a draw with the move before it, so PGN movetext can be read too.
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let contents: String = fs::read_to_string(path)?;
+        let mut moves: Vec<String> = Vec::new();
+        for token in contents.split_whitespace() {
+            if token.ends_with('.') {
+                continue;
+            }
+            match moves.last_mut() {
+                Some(last) if matches!(token, "draw" | "(=)" | "=") => last.push_str(" draw"),
+                _ => moves.push(token.to_string()),
+            }
+        }
+        Ok(ScriptedPlayer::new(moves))
+    }
+}
+
+impl Player for ScriptedPlayer {
+    fn request_move(&mut self, _board: &Board, _colour: PieceColour) -> PlayerAction {
+        let move_notation: &String = match self.moves.get(self.next) {
+            Some(move_notation) => move_notation,
+            None => return PlayerAction::Resign,
+        };
+        self.next += 1;
+
+        let action: PlayerAction = PlayerAction::parse(move_notation);
+        if let PlayerAction::Move { notation, .. } = &action {
+            if let Ok(CoordinateMove {
+                promotion: Some(promotion),
+                ..
+            }) = CoordinateMove::from_notation(notation)
+            {
+                self.promotion = promotion;
+            }
+        }
+        action
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        self.promotion
+    }
+
+    fn notify(&mut self, _notification: &Notification) {}
+}
@@ -1,6 +1,8 @@
 use crate::{board::board_columns, piece::PieceType};
+use std::fmt;
 
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Position {
     pub row: usize,
     pub column: usize,
@@ -10,9 +12,20 @@ impl Position {
     pub fn new(row: usize, column: usize) -> Self {
         Position { row, column }
     }
+
+    pub fn from_notation(notation: &str) -> Result<Self, NotationError> {
+        let notation_elements: Vec<char> = notation.trim().chars().collect();
+        if notation_elements.len() != 2 {
+            return Err(NotationError);
+        }
+        square_from_chars(notation_elements[0], notation_elements[1])
+    }
 }
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub struct NotationError;
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Move {
     pub piece_type: PieceType,
     pub new_position: Position,
@@ -98,7 +111,7 @@ impl Move {
         }
 
         if notation_elements.len() == 4 {
-            if notation_elements[1].is_digit(10) {
+            if notation_elements[1].is_ascii_digit() {
                 let starting_row: Option<usize> = match notation_elements[1].to_digit(10) {
                     Some(row) => Some(row as usize),
                     None => return Err(NotationError),
@@ -147,6 +160,8 @@ impl Move {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MoveResult {
     Success,
     PromotionAvailable(Position),
@@ -157,3 +172,126 @@ pub enum MoveResult {
     MissingPiece,
     Draw,
 }
+
+/// A move given as origin and destination squares, e.g. "e2e4" or "e7e8q", or a Crazyhouse
+/// drop such as "N@f3".
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CoordinateMove {
+    pub from: Position,
+    pub to: Position,
+    pub promotion: Option<PieceType>,
+    /// The piece put down from the pocket onto `to`, which `from` is then the same as.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub drop: Option<PieceType>,
+}
+
+impl CoordinateMove {
+    pub fn new(from: Position, to: Position, promotion: Option<PieceType>) -> Self {
+        CoordinateMove {
+            from,
+            to,
+            promotion,
+            drop: None,
+        }
+    }
+
+    /// `piece_type` dropped from the pocket onto `to`.
+    pub fn new_drop(piece_type: PieceType, to: Position) -> Self {
+        CoordinateMove {
+            from: to,
+            to,
+            promotion: None,
+            drop: Some(piece_type),
+        }
+    }
+
+    pub fn from_notation(notation: &str) -> Result<Self, NotationError> {
+        let notation_elements: Vec<char> = notation.trim().chars().collect();
+
+        // drops: "N@f3", or "@e4" and "P@e4" for pawns
+        if let Some(at) = notation_elements.iter().position(|&c| c == '@') {
+            let piece_type: PieceType = match at {
+                0 => PieceType::Pawn,
+                1 => PieceType::from_letter(notation_elements[0])
+                    .filter(|&piece_type| piece_type != PieceType::King)
+                    .ok_or(NotationError)?,
+                _ => return Err(NotationError),
+            };
+            if notation_elements.len() != at + 3 {
+                return Err(NotationError);
+            }
+            let to: Position =
+                square_from_chars(notation_elements[at + 1], notation_elements[at + 2])?;
+            return Ok(CoordinateMove::new_drop(piece_type, to));
+        }
+
+        if notation_elements.len() != 4 && notation_elements.len() != 5 {
+            return Err(NotationError);
+        }
+
+        let from: Position = square_from_chars(notation_elements[0], notation_elements[1])?;
+        let to: Position = square_from_chars(notation_elements[2], notation_elements[3])?;
+
+        let promotion: Option<PieceType> = match notation_elements.get(4) {
+            None => None,
+            Some(symbol) => match symbol.to_ascii_lowercase() {
+                'q' => Some(PieceType::Queen),
+                'r' => Some(PieceType::Rook),
+                'b' => Some(PieceType::Bishop),
+                'n' => Some(PieceType::Knight),
+                _ => return Err(NotationError),
+            },
+        };
+
+        Ok(CoordinateMove::new(from, to, promotion))
+    }
+
+    pub fn to_notation(self) -> String {
+        if let Some(piece_type) = self.drop {
+            return format!("{}@{}", piece_type.letter(), self.to);
+        }
+        let mut notation: String = format!("{}{}", self.from, self.to);
+        match self.promotion {
+            Some(PieceType::Queen) => notation.push('q'),
+            Some(PieceType::Rook) => notation.push('r'),
+            Some(PieceType::Bishop) => notation.push('b'),
+            Some(PieceType::Knight) => notation.push('n'),
+            _ => (),
+        }
+        notation
+    }
+}
+
+pub fn column_from_char(letter: char) -> Option<usize> {
+    match letter.to_ascii_uppercase() {
+        'A' => Some(board_columns::A),
+        'B' => Some(board_columns::B),
+        'C' => Some(board_columns::C),
+        'D' => Some(board_columns::D),
+        'E' => Some(board_columns::E),
+        'F' => Some(board_columns::F),
+        'G' => Some(board_columns::G),
+        'H' => Some(board_columns::H),
+        _ => None,
+    }
+}
+
+pub fn column_to_char(column: usize) -> char {
+    (b'a' + (column - board_columns::A) as u8) as char
+}
+
+fn square_from_chars(letter: char, digit: char) -> Result<Position, NotationError> {
+    let column: usize = column_from_char(letter).ok_or(NotationError)?;
+    let row: usize = match digit.to_digit(10) {
+        Some(row @ 1..=8) => row as usize,
+        _ => return Err(NotationError),
+    };
+    Ok(Position::new(row, column))
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", column_to_char(self.column), self.row)
+    }
+}
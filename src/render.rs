@@ -0,0 +1,417 @@
+//! Everything the game shows goes through a `Renderer`, so the game itself never writes to the
+//! terminal. `AnsiRenderer` draws the interactive screen, redrawing the clocks in place, and
+//! `PlainRenderer` writes the same frames as plain lines for logs and pipes. Both write to any
+//! `Write`, so frames can be captured in memory. The full-screen interface behind the `tui`
+//! feature lives in `tui.rs`.
+
+use crate::{
+    board::Board,
+    pgn::MoveRecord,
+    piece::PieceColour,
+    position::{column_to_char, Position},
+    style::Style,
+};
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub const ESC: &str = "\x1B[";
+const LAST_MOVE_BACKGROUND: &str = "43";
+const CHECK_BACKGROUND: &str = "41";
+const DESTINATION_BACKGROUND: &str = "42";
+
+/// The places a line of text can be shown.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Line {
+    /// Asks the player for input.
+    Prompt,
+    /// The move the opponent just played.
+    OpponentMove,
+    /// Draw offers and their answers.
+    DrawOffer,
+    /// Errors, results and anything else, shown below the last line written.
+    Status,
+}
+
+/// Which side of the board is drawn at the bottom.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    White,
+    Black,
+    /// Whoever is to move.
+    Auto,
+}
+
+impl Orientation {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "white" => Some(Orientation::White),
+            "black" => Some(Orientation::Black),
+            "auto" => Some(Orientation::Auto),
+            _ => None,
+        }
+    }
+
+    pub fn bottom(self, turn: PieceColour) -> PieceColour {
+        match self {
+            Orientation::White => PieceColour::White,
+            Orientation::Black => PieceColour::Black,
+            Orientation::Auto => turn,
+        }
+    }
+}
+
+/// Everything drawn when the screen is redrawn.
+pub struct Frame<'a> {
+    pub board: &'a Board,
+    /// The side to move.
+    pub turn: PieceColour,
+    /// Both clocks as (white, black), when the game is timed.
+    pub clocks: Option<(Duration, Duration)>,
+    /// Where the piece the player asked about with `moves <square>` can go.
+    pub destinations: &'a [Position],
+    /// The moves played so far, for renderers with room for a move list.
+    pub moves: &'a [MoveRecord],
+    /// What the variant shows beside each side's clock, as (white, black), such as the checks
+    /// given in Three-check. Empty for nothing.
+    pub notes: (String, String),
+}
+
+pub trait Renderer: Send {
+    /// Replaces the screen with a new frame.
+    fn frame(&mut self, frame: &Frame);
+
+    /// Shows the time left on the running clock.
+    fn tick(&mut self, colour: PieceColour, remaining: Duration);
+
+    fn line(&mut self, line: Line, text: &str);
+
+    /// Gives the terminal back before the program ends.
+    fn close(&mut self) {}
+}
+
+/// The renderer shared by the game, its clock thread and the players at the terminal.
+pub type SharedRenderer = Arc<Mutex<dyn Renderer>>;
+
+/// Picks the ANSI renderer for a terminal and the plain one otherwise.
+pub fn for_stdout(plain: bool, orientation: Orientation, style: Style) -> SharedRenderer {
+    if plain || !io::IsTerminal::is_terminal(&io::stdout()) {
+        Arc::new(Mutex::new(PlainRenderer::new(
+            Box::new(io::stdout()),
+            orientation,
+            style,
+        )))
+    } else {
+        Arc::new(Mutex::new(AnsiRenderer::new(
+            Box::new(io::stdout()),
+            orientation,
+            style,
+        )))
+    }
+}
+
+pub struct AnsiRenderer {
+    output: Box<dyn Write + Send>,
+    orientation: Orientation,
+    style: Style,
+    bottom: PieceColour,
+    /// What each side has taken as of the last frame, as (white, black), shown after its clock.
+    captures: (String, String),
+    /// The variant's notes from the last frame, shown after the captures.
+    notes: (String, String),
+}
+
+impl AnsiRenderer {
+    pub fn new(output: Box<dyn Write + Send>, orientation: Orientation, style: Style) -> Self {
+        AnsiRenderer {
+            output,
+            orientation,
+            style,
+            bottom: orientation.bottom(PieceColour::White),
+            captures: (String::new(), String::new()),
+            notes: (String::new(), String::new()),
+        }
+    }
+
+    /// The clock of the side at the bottom goes below the board, the other one above it.
+    fn clock_row(&self, colour: PieceColour) -> u32 {
+        if colour == self.bottom {
+            13
+        } else {
+            1
+        }
+    }
+
+    /// The clock, captured pieces and variant note of `colour`.
+    fn side_text(&self, colour: PieceColour, remaining: Option<Duration>) -> String {
+        let (captures, note): (&str, &str) = match colour {
+            PieceColour::White => (&self.captures.0, &self.notes.0),
+            PieceColour::Black => (&self.captures.1, &self.notes.1),
+        };
+        let parts: Vec<String> = [remaining.map(format_clock).unwrap_or_default()]
+            .into_iter()
+            .chain([captures, note].map(str::to_string))
+            .filter(|part| !part.is_empty())
+            .collect();
+        parts.join("  ")
+    }
+}
+
+impl Renderer for AnsiRenderer {
+    fn frame(&mut self, frame: &Frame) {
+        self.bottom = self.orientation.bottom(frame.turn);
+        self.captures = (
+            captures(frame.board, PieceColour::White, &self.style),
+            captures(frame.board, PieceColour::Black, &self.style),
+        );
+        self.notes = frame.notes.clone();
+        let top: PieceColour = self.bottom.opponent();
+        let mut text: String = format!("{}2J{}H", ESC, ESC);
+        text.push_str(&format!(
+            "{}\n",
+            self.side_text(top, frame.clocks.map(|clocks| clock_of(clocks, top)))
+        ));
+        let highlights: Vec<(Position, &str)> = highlights(frame);
+        text.push_str(&format!(
+            "{}1;0H\n{}\n",
+            ESC,
+            diagram(frame.board, self.bottom, &highlights, &self.style)
+        ));
+        text.push_str(&format!(
+            "{}13;0H{}\n",
+            ESC,
+            self.side_text(
+                self.bottom,
+                frame.clocks.map(|clocks| clock_of(clocks, self.bottom))
+            )
+        ));
+        let _ = self.output.write_all(text.as_bytes());
+        let _ = self.output.flush();
+    }
+
+    fn tick(&mut self, colour: PieceColour, remaining: Duration) {
+        let _ = write!(
+            self.output,
+            "{}s{}{};0H{}2K{}\n{}u",
+            ESC,
+            ESC,
+            self.clock_row(colour),
+            ESC,
+            self.side_text(colour, Some(remaining)),
+            ESC
+        );
+        let _ = self.output.flush();
+    }
+
+    fn line(&mut self, line: Line, text: &str) {
+        let _ = match line {
+            // leaves the cursor on a cleared line for the player to type on
+            Line::Prompt => write!(self.output, "{}14;0H{}2K{}\n{}2K", ESC, ESC, text, ESC),
+            Line::OpponentMove => writeln!(self.output, "{}16;0H{}2K{}", ESC, ESC, text),
+            Line::DrawOffer => writeln!(self.output, "{}17;0H{}2K{}", ESC, ESC, text),
+            Line::Status => writeln!(self.output, "{}2K{}", ESC, text),
+        };
+        let _ = self.output.flush();
+    }
+}
+
+pub struct PlainRenderer {
+    output: Box<dyn Write + Send>,
+    orientation: Orientation,
+    style: Style,
+}
+
+impl PlainRenderer {
+    pub fn new(output: Box<dyn Write + Send>, orientation: Orientation, style: Style) -> Self {
+        PlainRenderer {
+            output,
+            orientation,
+            style,
+        }
+    }
+}
+
+impl Renderer for PlainRenderer {
+    /// Without colours, the squares a piece can move to are listed below the board instead.
+    fn frame(&mut self, frame: &Frame) {
+        let bottom: PieceColour = self.orientation.bottom(frame.turn);
+        let mut text: String = side_line(frame, bottom.opponent(), &self.style);
+        text.push_str(&diagram(frame.board, bottom, &[], &self.style));
+        text.push_str(&side_line(frame, bottom, &self.style));
+        if !frame.destinations.is_empty() {
+            let squares: Vec<String> = frame
+                .destinations
+                .iter()
+                .map(|square| square.to_string())
+                .collect();
+            text.push_str(&format!("Can move to {}\n", squares.join(" ")));
+        }
+        let _ = self.output.write_all(text.as_bytes());
+        let _ = self.output.flush();
+    }
+
+    /// Clocks only appear with each frame, so logs do not fill up with ticks.
+    fn tick(&mut self, _colour: PieceColour, _remaining: Duration) {}
+
+    fn line(&mut self, _line: Line, text: &str) {
+        let _ = writeln!(self.output, "{}", text);
+        let _ = self.output.flush();
+    }
+}
+
+/// A side's name, clock, captured pieces and variant note, or nothing when there is none of
+/// them to show.
+fn side_line(frame: &Frame, colour: PieceColour, style: &Style) -> String {
+    let mut parts: Vec<String> = vec![colour_name(colour).to_string()];
+    if let Some(clocks) = frame.clocks {
+        parts.push(format_clock(clock_of(clocks, colour)));
+    }
+    let captures: String = captures(frame.board, colour, style);
+    if !captures.is_empty() {
+        parts.push(captures);
+    }
+    let note: &str = match colour {
+        PieceColour::White => &frame.notes.0,
+        PieceColour::Black => &frame.notes.1,
+    };
+    if !note.is_empty() {
+        parts.push(note.to_string());
+    }
+    if parts.len() == 1 {
+        return String::new();
+    }
+    format!("{}\n", parts.join(" "))
+}
+
+/// The pieces `colour` has taken, followed by its lead in material when it is ahead, as in
+/// "♙♙♘ +4".
+pub fn captures(board: &Board, colour: PieceColour, style: &Style) -> String {
+    // with pockets the captured pieces are shown as the pocket instead
+    if board.has_pockets() {
+        return String::new();
+    }
+    let mut text: String = board
+        .captured(colour.opponent())
+        .into_iter()
+        .map(|piece_type| style.pieces.symbol(piece_type, colour.opponent()))
+        .collect();
+    let lead: i32 = board.material(colour) - board.material(colour.opponent());
+    if lead > 0 {
+        if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&format!("+{}", lead));
+    }
+    text
+}
+
+/// Minutes, seconds and tenths, as in "9:59.7".
+pub fn format_clock(remaining: Duration) -> String {
+    format!(
+        "{}:{}.{}",
+        remaining.as_secs() / 60,
+        remaining.as_secs() % 60,
+        remaining.subsec_millis() / 100
+    )
+}
+
+pub fn clock_of(clocks: (Duration, Duration), colour: PieceColour) -> Duration {
+    match colour {
+        PieceColour::White => clocks.0,
+        PieceColour::Black => clocks.1,
+    }
+}
+
+pub fn colour_name(colour: PieceColour) -> &'static str {
+    match colour {
+        PieceColour::White => "White",
+        PieceColour::Black => "Black",
+    }
+}
+
+/// Background colours for the squares of the last move, a king in check and the destinations
+/// asked for, later entries winning where they overlap.
+pub fn highlights<'a>(frame: &Frame) -> Vec<(Position, &'a str)> {
+    let mut highlights: Vec<(Position, &str)> = Vec::new();
+    if let Some(last_move) = frame.board.last_move() {
+        highlights.push((last_move.from, LAST_MOVE_BACKGROUND));
+        highlights.push((last_move.to, LAST_MOVE_BACKGROUND));
+    }
+    if let Some(king) = frame.board.checked_king(frame.turn) {
+        highlights.push((king, CHECK_BACKGROUND));
+    }
+    for destination in frame.destinations {
+        highlights.push((*destination, DESTINATION_BACKGROUND));
+    }
+    highlights
+}
+
+/// The board drawn with text characters in `style`, with `bottom`'s pieces starting at the
+/// bottom. Squares in `highlights` get the given ANSI background colour.
+pub fn diagram(
+    board: &Board,
+    bottom: PieceColour,
+    highlights: &[(Position, &str)],
+    style: &Style,
+) -> String {
+    let rows: Vec<usize> = match bottom {
+        PieceColour::White => (1..=8).rev().collect(),
+        PieceColour::Black => (1..=8).collect(),
+    };
+    let columns: Vec<usize> = match bottom {
+        PieceColour::White => (1..=8).collect(),
+        PieceColour::Black => (1..=8).rev().collect(),
+    };
+    let (top_border, bottom_border): (char, char) = style.squares.borders();
+    let mut ascii_graphic: String = String::new();
+
+    ascii_graphic.push_str("  ");
+    for _ in 1..=16 {
+        ascii_graphic.push(top_border);
+    }
+    ascii_graphic.push('\n');
+
+    for &row in &rows {
+        ascii_graphic.push_str(&row.to_string());
+        ascii_graphic.push('|');
+
+        for &column in &columns {
+            let position: Position = Position::new(row, column);
+            let highlight: Option<&str> = highlights
+                .iter()
+                .rev()
+                .find(|(square, _)| *square == position)
+                .map(|(_, background)| *background);
+            let symbol: Option<char> = board
+                .piece_at(position)
+                .map(|p| style.pieces.symbol(p.piece_type, p.colour));
+            if let Some(background) = highlight {
+                let symbol: char = symbol.unwrap_or(' ');
+                ascii_graphic.push_str(&format!("{}{}m{} {}0m", ESC, background, symbol, ESC));
+            } else if let Some(symbol) = symbol {
+                ascii_graphic.push_str(&format!("{} ", symbol));
+            } else if (row % 2) + (column % 2) == 1 {
+                ascii_graphic.push_str("  ");
+            } else {
+                ascii_graphic.push_str(style.squares.dark());
+            }
+        }
+
+        ascii_graphic.push('|');
+        ascii_graphic.push('\n');
+    }
+    ascii_graphic.push_str("  ");
+
+    for _ in 1..=16 {
+        ascii_graphic.push(bottom_border);
+    }
+    ascii_graphic.push('\n');
+    ascii_graphic.push_str("  ");
+
+    for &column in &columns {
+        ascii_graphic.push(column_to_char(column));
+        ascii_graphic.push(' ');
+    }
+    ascii_graphic.push('\n');
+    ascii_graphic
+}
@@ -0,0 +1,152 @@
+//! How a game ended, as returned by `GameManager::start_game`. The `chess` program exits with
+//! [`GameResult::exit_code`], so scripts can tell the outcome without reading its output:
+//!
+//! ```text
+//! 0   the game was adjourned
+//! 1   the game could not be played (bad options, files or connections)
+//! 10  White won
+//! 11  Black won
+//! 12  draw
+//! ```
+
+use crate::{
+    board::Board, pgn::MoveRecord, piece::PieceColour, position::MoveResult, variant::Variant,
+};
+use std::fmt;
+
+pub const EXIT_ADJOURNED: i32 = 0;
+pub const EXIT_WHITE_WON: i32 = 10;
+pub const EXIT_BLACK_WON: i32 = 11;
+pub const EXIT_DRAW: i32 = 12;
+
+/// Why the game ended.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Termination {
+    Checkmate,
+    Resignation,
+    /// A clock ran out.
+    Timeout,
+    Stalemate,
+    /// The players agreed to a draw.
+    Agreement,
+    /// The same position came up for the third time.
+    Repetition,
+    /// Fifty moves each without a capture or a pawn move.
+    FiftyMove,
+    /// Neither side has enough pieces left to checkmate.
+    InsufficientMaterial,
+    /// A player left the game, such as a network opponent losing the connection.
+    Abandonment,
+    /// The game was saved to be finished later; there is no winner yet.
+    Adjourned,
+    /// A king reached the centre in King of the Hill.
+    CentreReached,
+    /// The third check in Three-check.
+    ThreeChecks,
+    /// A capture next to the king blew it up in atomic chess.
+    KingExploded,
+}
+
+impl fmt::Display for Termination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Termination::Checkmate => "checkmate",
+            Termination::Resignation => "resignation",
+            Termination::Timeout => "timeout",
+            Termination::Stalemate => "stalemate",
+            Termination::Agreement => "agreement",
+            Termination::Repetition => "threefold repetition",
+            Termination::FiftyMove => "fifty-move rule",
+            Termination::InsufficientMaterial => "insufficient material",
+            Termination::Abandonment => "abandonment",
+            Termination::Adjourned => "adjournment",
+            Termination::CentreReached => "king reaching the centre",
+            Termination::ThreeChecks => "three checks",
+            Termination::KingExploded => "exploding the king",
+        })
+    }
+}
+
+/// The end of a game: who won, why, and how it got there.
+#[derive(Clone)]
+pub struct GameResult {
+    /// `None` for a draw or an adjourned game.
+    pub winner: Option<PieceColour>,
+    pub termination: Termination,
+    /// The final position, with the side that would have moved next.
+    pub board: Board,
+    pub turn: PieceColour,
+    pub moves: Vec<MoveRecord>,
+}
+
+impl GameResult {
+    /// The result as written in PGN: "1-0", "0-1", "1/2-1/2", or "*" for an unfinished game.
+    pub fn score(&self) -> &'static str {
+        score(self.winner, self.termination)
+    }
+
+    /// The final position in FEN.
+    pub fn fen(&self) -> String {
+        self.board.to_fen(self.turn, self.moves.len() / 2 + 1)
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match (self.winner, self.termination) {
+            (_, Termination::Adjourned) => EXIT_ADJOURNED,
+            (Some(PieceColour::White), _) => EXIT_WHITE_WON,
+            (Some(PieceColour::Black), _) => EXIT_BLACK_WON,
+            (None, _) => EXIT_DRAW,
+        }
+    }
+}
+
+impl fmt::Display for GameResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.winner, self.termination) {
+            (_, Termination::Adjourned) => f.write_str("Game adjourned"),
+            (Some(PieceColour::White), termination) => write!(f, "White won by {}", termination),
+            (Some(PieceColour::Black), termination) => write!(f, "Black won by {}", termination),
+            (None, termination) => write!(f, "Draw by {}", termination),
+        }
+    }
+}
+
+pub fn score(winner: Option<PieceColour>, termination: Termination) -> &'static str {
+    match (winner, termination) {
+        (_, Termination::Adjourned) => "*",
+        (Some(PieceColour::White), _) => "1-0",
+        (Some(PieceColour::Black), _) => "0-1",
+        (None, _) => "1/2-1/2",
+    }
+}
+
+/// How the game ends after `mover`'s move, if it does, checked the same way in live and
+/// correspondence games. `variant_win` is what `Variant::after_move` returned for the move,
+/// `move_result` what the board did with it and `repetitions` how often the position it left
+/// has now come up.
+pub fn ending(
+    board: &mut Board,
+    mover: PieceColour,
+    variant: &dyn Variant,
+    variant_win: Option<Termination>,
+    move_result: MoveResult,
+    repetitions: u32,
+) -> Option<(Option<PieceColour>, Termination)> {
+    let next: PieceColour = mover.opponent();
+    if let Some(termination) = variant_win {
+        Some((Some(mover), termination))
+    } else if board.is_mate(next) {
+        Some((Some(mover), Termination::Checkmate))
+    } else if board.is_stalemate(next) {
+        Some((None, Termination::Stalemate))
+    } else if variant.insufficient_material(board) {
+        Some((None, Termination::InsufficientMaterial))
+    } else if move_result == MoveResult::Draw {
+        Some((None, Termination::FiftyMove))
+    } else if repetitions >= 3 {
+        Some((None, Termination::Repetition))
+    } else {
+        None
+    }
+}
@@ -0,0 +1,300 @@
+//! Saved (adjourned) games are plain text, one `key value` pair per line:
+//!
+//! ```text
+//! chess-save 1
+//! time-control 10
+//! start rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+//! moves e2e4 e7e5= g1f3
+//! position rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2
+//! white-clock 582300
+//! black-clock 597100
+//! draw-offer none
+//! ```
+//!
+//! `time-control` is the minutes each side started with and the clocks are the milliseconds
+//! left. `moves` lists every move from the `start` position in coordinate notation, a trailing
+//! `=` marking a move played with a draw offer. The game is rebuilt by replaying them, and
+//! `position` (with the side to move) must match the result. `draw-offer` names the side whose
+//! offer is still waiting for an answer, or `none`.
+//!
+//! Games of a variant other than standard chess add a `variant` line with its PGN name, such
+//! as `variant Three-check`, and write their positions in that variant's FEN.
+//!
+//! Correspondence games have no clocks, so they leave out `white-clock` and `black-clock`
+//! and add:
+//!
+//! ```text
+//! days-per-move 3
+//! started 1792310400
+//! timestamps 1792314000 1792400000 1792486400
+//! result 1-0 checkmate
+//! ```
+//!
+//! `started` and `timestamps` are seconds since the Unix epoch, one timestamp per move, and
+//! the side to move forfeits once `days-per-move` days have passed since the last of them.
+//! `result` is only present once the game is over.
+
+use crate::{
+    board::Board,
+    error::invalid,
+    pgn::MoveRecord,
+    piece::PieceColour,
+    position::{CoordinateMove, MoveResult},
+    variant::{self, Standard, Variant},
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+const HEADER: &str = "chess-save 1";
+
+/// The record of a game, as kept in save files. With the `serde` feature it can be stored or sent
+/// in any serde format as well, such as JSON.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SavedGame {
+    pub minutes: u64,
+    pub start: String,
+    pub moves: Vec<(CoordinateMove, bool)>,
+    pub position: String,
+    pub white_clock: Duration,
+    pub black_clock: Duration,
+    pub draw_offer: Option<PieceColour>,
+    pub days_per_move: Option<u64>,
+    pub started: Option<u64>,
+    pub timestamps: Vec<u64>,
+    pub result: Option<String>,
+    /// `None` for standard chess.
+    pub variant: Option<String>,
+}
+
+/// The game rebuilt from a save file.
+pub struct Replay {
+    pub board: Board,
+    pub turn: PieceColour,
+    pub moves: Vec<MoveRecord>,
+    pub position_counts: HashMap<String, u32>,
+    /// The variant, with whatever it keeps track of brought up to date.
+    pub variant: Box<dyn Variant>,
+}
+
+impl SavedGame {
+    /// Replays the saved moves from the start position, checking that each one is legal and
+    /// that they lead to the saved position.
+    pub fn replay(&self) -> io::Result<Replay> {
+        let mut variant: Box<dyn Variant> = self.new_variant()?;
+        let (mut board, mut turn): (Board, PieceColour) = variant
+            .read_fen(&self.start)
+            .map_err(|_| invalid("bad start position"))?;
+        let mut moves: Vec<MoveRecord> = Vec::new();
+        let mut position_counts: HashMap<String, u32> = HashMap::new();
+        position_counts.insert(board.position_key(turn), 1);
+
+        for (coordinate_move, draw_offer) in &self.moves {
+            let board_before_move: Board = board.clone();
+            match board.play_coordinate_move(turn, coordinate_move) {
+                MoveResult::Success | MoveResult::Draw => (),
+                _ => return Err(invalid("illegal move")),
+            }
+            moves.push(MoveRecord {
+                coordinate_move: *coordinate_move,
+                san: board_before_move.san(turn, coordinate_move),
+                draw_offer: *draw_offer,
+            });
+            variant.after_move(&board, turn);
+            turn = turn.opponent();
+            *position_counts.entry(board.position_key(turn)).or_insert(0) += 1;
+        }
+
+        if !self.position.is_empty() {
+            let (position, position_turn): (Board, PieceColour) = self
+                .new_variant()?
+                .read_fen(&self.position)
+                .map_err(|_| invalid("bad position"))?;
+            if position.position_key(position_turn) != board.position_key(turn) {
+                return Err(invalid("moves do not lead to the saved position"));
+            }
+        }
+
+        Ok(Replay {
+            board,
+            turn,
+            moves,
+            position_counts,
+            variant,
+        })
+    }
+
+    /// The saved game's variant, as it was before any moves.
+    pub fn new_variant(&self) -> io::Result<Box<dyn Variant>> {
+        match &self.variant {
+            Some(name) => variant::from_name(name).ok_or_else(|| invalid("unknown variant")),
+            None => Ok(Box::new(Standard)),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|(coordinate_move, draw_offer)| {
+                let mut notation: String = coordinate_move.to_notation();
+                if *draw_offer {
+                    notation.push('=');
+                }
+                notation
+            })
+            .collect();
+        let draw_offer: &str = match self.draw_offer {
+            Some(PieceColour::White) => "white",
+            Some(PieceColour::Black) => "black",
+            None => "none",
+        };
+
+        let mut text: String = format!("{}\ntime-control {}\n", HEADER, self.minutes);
+        if let Some(variant) = &self.variant {
+            text.push_str(&format!("variant {}\n", variant));
+        }
+        text.push_str(&format!(
+            "start {}\nmoves {}\nposition {}\n",
+            self.start,
+            moves.join(" "),
+            self.position
+        ));
+        match self.days_per_move {
+            Some(days_per_move) => {
+                text.push_str(&format!("days-per-move {}\n", days_per_move));
+                if let Some(started) = self.started {
+                    text.push_str(&format!("started {}\n", started));
+                }
+                let timestamps: Vec<String> =
+                    self.timestamps.iter().map(|t| t.to_string()).collect();
+                text.push_str(&format!("timestamps {}\n", timestamps.join(" ")));
+            }
+            None => {
+                text.push_str(&format!(
+                    "white-clock {}\nblack-clock {}\n",
+                    self.white_clock.as_millis(),
+                    self.black_clock.as_millis()
+                ));
+            }
+        }
+        text.push_str(&format!("draw-offer {}\n", draw_offer));
+        if let Some(result) = &self.result {
+            text.push_str(&format!("result {}\n", result));
+        }
+        text
+    }
+
+    /// Reads a game written by `to_text`, which writes it back the same:
+    ///
+    /// ```
+    /// use chess::save::SavedGame;
+    ///
+    /// let text = "chess-save 1
+    /// time-control 10
+    /// start rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+    /// moves e2e4 e7e5 g1f3=
+    /// position rnbqkbnr/pppp1ppp/8/4p3/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq - 1 2
+    /// white-clock 582300
+    /// black-clock 597100
+    /// draw-offer white
+    /// ";
+    /// let saved = SavedGame::parse(text).unwrap();
+    /// assert_eq!(saved.moves.len(), 3);
+    /// assert!(saved.moves[2].1);
+    /// assert_eq!(saved.to_text(), text);
+    /// assert!(saved.replay().is_ok());
+    /// ```
+    pub fn parse(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines();
+        if lines.next().map(str::trim) != Some(HEADER) {
+            return Err(invalid("not a saved game"));
+        }
+
+        let mut saved: SavedGame = SavedGame {
+            minutes: 10,
+            start: String::new(),
+            moves: Vec::new(),
+            position: String::new(),
+            white_clock: Duration::ZERO,
+            black_clock: Duration::ZERO,
+            draw_offer: None,
+            days_per_move: None,
+            started: None,
+            timestamps: Vec::new(),
+            result: None,
+            variant: None,
+        };
+
+        for line in lines {
+            let (key, value): (&str, &str) = match line.trim().split_once(' ') {
+                Some((key, value)) => (key, value.trim()),
+                None => (line.trim(), ""),
+            };
+            match key {
+                "" => (),
+                "time-control" => {
+                    saved.minutes = value.parse().map_err(|_| invalid("bad time control"))?
+                }
+                "variant" => saved.variant = Some(value.to_string()),
+                "start" => saved.start = value.to_string(),
+                "moves" => {
+                    for notation in value.split_whitespace() {
+                        let draw_offer: bool = notation.ends_with('=');
+                        let coordinate_move: CoordinateMove =
+                            CoordinateMove::from_notation(notation.trim_end_matches('='))
+                                .map_err(|_| invalid("bad move"))?;
+                        saved.moves.push((coordinate_move, draw_offer));
+                    }
+                }
+                "position" => saved.position = value.to_string(),
+                "white-clock" => saved.white_clock = parse_clock(value)?,
+                "black-clock" => saved.black_clock = parse_clock(value)?,
+                "draw-offer" => {
+                    saved.draw_offer = match value {
+                        "white" => Some(PieceColour::White),
+                        "black" => Some(PieceColour::Black),
+                        "none" => None,
+                        _ => return Err(invalid("bad draw offer")),
+                    }
+                }
+                "days-per-move" => {
+                    saved.days_per_move =
+                        Some(value.parse().map_err(|_| invalid("bad days per move"))?)
+                }
+                "started" => {
+                    saved.started = Some(value.parse().map_err(|_| invalid("bad timestamp"))?)
+                }
+                "timestamps" => {
+                    for timestamp in value.split_whitespace() {
+                        saved
+                            .timestamps
+                            .push(timestamp.parse().map_err(|_| invalid("bad timestamp"))?);
+                    }
+                }
+                "result" => saved.result = Some(value.to_string()),
+                _ => return Err(invalid("unknown field")),
+            }
+        }
+
+        if saved.start.is_empty() {
+            return Err(invalid("missing start position"));
+        }
+        Ok(saved)
+    }
+
+    pub fn read(path: &Path) -> io::Result<Self> {
+        SavedGame::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+fn parse_clock(value: &str) -> io::Result<Duration> {
+    let milliseconds: u64 = value.parse().map_err(|_| invalid("bad clock"))?;
+    Ok(Duration::from_millis(milliseconds))
+}
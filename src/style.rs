@@ -0,0 +1,169 @@
+//! How the terminal renderers draw pieces and empty squares, chosen at startup with `--pieces`
+//! and `--squares`. Besides the built-in `unicode` and `ascii` sets, `--pieces` takes the path of
+//! a plain text file with one `key value` pair per line:
+//!
+//! ```text
+//! white KQRBNP
+//! black kqrbnp
+//! squares dots
+//! ```
+//!
+//! `white` and `black` give the six symbols of that side's king, queen, rook, bishop, knight and
+//! pawn, in that order. `squares` is optional and picks the square style unless `--squares`
+//! is also given. Lines starting with `#` are ignored.
+
+use crate::{
+    error,
+    piece::{PieceColour, PieceType},
+};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The order of the symbols in a piece set.
+const ORDER: [PieceType; 6] = [
+    PieceType::King,
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+    PieceType::Pawn,
+];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PieceSet {
+    white: [char; 6],
+    black: [char; 6],
+}
+
+impl PieceSet {
+    pub const UNICODE: PieceSet = PieceSet {
+        white: ['♚', '♛', '♜', '♝', '♞', '♟'],
+        black: ['♔', '♕', '♖', '♗', '♘', '♙'],
+    };
+
+    /// The letters used in FEN, for terminals and logs without chess glyphs.
+    pub const ASCII: PieceSet = PieceSet {
+        white: ['K', 'Q', 'R', 'B', 'N', 'P'],
+        black: ['k', 'q', 'r', 'b', 'n', 'p'],
+    };
+
+    pub fn symbol(&self, piece_type: PieceType, colour: PieceColour) -> char {
+        let index: usize = ORDER.iter().position(|t| *t == piece_type).unwrap();
+        match colour {
+            PieceColour::White => self.white[index],
+            PieceColour::Black => self.black[index],
+        }
+    }
+}
+
+/// How empty dark squares and the lines around the board are drawn; empty light squares are
+/// always blank.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SquareStyle {
+    Blocks,
+    Shaded,
+    Dots,
+}
+
+impl SquareStyle {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "blocks" => Some(SquareStyle::Blocks),
+            "shaded" => Some(SquareStyle::Shaded),
+            "dots" => Some(SquareStyle::Dots),
+            _ => None,
+        }
+    }
+
+    /// The two columns of an empty dark square.
+    pub fn dark(self) -> &'static str {
+        match self {
+            SquareStyle::Blocks => "██",
+            SquareStyle::Shaded => "░░",
+            SquareStyle::Dots => ". ",
+        }
+    }
+
+    /// The characters of the lines above and below the board.
+    pub fn borders(self) -> (char, char) {
+        match self {
+            SquareStyle::Blocks | SquareStyle::Shaded => ('_', '‾'),
+            // keeps boards drawn with ASCII pieces entirely in ASCII
+            SquareStyle::Dots => ('-', '-'),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Style {
+    pub pieces: PieceSet,
+    pub squares: SquareStyle,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Style {
+            pieces: PieceSet::UNICODE,
+            squares: SquareStyle::Blocks,
+        }
+    }
+}
+
+impl Style {
+    /// Builds the style asked for on the command line: `pieces` is "unicode", "ascii" or the
+    /// path of a piece set file, and `squares` the name of a square style.
+    pub fn from_options(pieces: Option<&str>, squares: Option<&str>) -> io::Result<Self> {
+        let mut style: Style = match pieces {
+            None | Some("unicode") => Style::default(),
+            Some("ascii") => Style {
+                pieces: PieceSet::ASCII,
+                squares: SquareStyle::Dots,
+            },
+            Some(path) => Style::read(Path::new(path))?,
+        };
+        if let Some(name) = squares {
+            style.squares = SquareStyle::from_name(name)
+                .ok_or_else(|| error::invalid("unknown square style"))?;
+        }
+        Ok(style)
+    }
+
+    fn read(path: &Path) -> io::Result<Self> {
+        let contents: String = fs::read_to_string(path)?;
+        let mut style: Style = Style::default();
+        let (mut white, mut black): (Option<[char; 6]>, Option<[char; 6]>) = (None, None);
+        for line in contents.lines() {
+            let line: &str = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value): (&str, &str) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| error::invalid("missing value"))?;
+            let value: &str = value.trim();
+            match key {
+                "white" => white = Some(symbols(value)?),
+                "black" => black = Some(symbols(value)?),
+                "squares" => {
+                    style.squares = SquareStyle::from_name(value)
+                        .ok_or_else(|| error::invalid("unknown square style"))?
+                }
+                _ => return Err(error::invalid("unknown key")),
+            }
+        }
+        style.pieces = PieceSet {
+            white: white.ok_or_else(|| error::invalid("missing white pieces"))?,
+            black: black.ok_or_else(|| error::invalid("missing black pieces"))?,
+        };
+        Ok(style)
+    }
+}
+
+/// One side's symbols, which may be separated by spaces.
+fn symbols(value: &str) -> io::Result<[char; 6]> {
+    let symbols: Vec<char> = value.chars().filter(|c| !c.is_whitespace()).collect();
+    symbols
+        .try_into()
+        .map_err(|_| error::invalid("a side needs exactly six piece symbols"))
+}
@@ -0,0 +1,221 @@
+//! Board diagrams as standalone SVG images, for reports and tickets. Pieces are drawn with the
+//! chess glyphs of whatever font the viewer has, so the files stay small and need nothing
+//! beside them.
+//!
+//! `chess svg` writes them from the command line.
+
+use crate::{
+    board::Board,
+    piece::{PieceColour, PieceType},
+    position::{column_to_char, Position},
+};
+
+/// The width of a square, in pixels.
+const SQUARE: usize = 45;
+/// Room for the coordinates around the board.
+const MARGIN: usize = 20;
+const LIGHT_SQUARE: &str = "#f0d9b5";
+const DARK_SQUARE: &str = "#b58863";
+const HIGHLIGHT: &str = "#f7ec5a";
+const ARROW: &str = "#15781b";
+const ARROW_WIDTH: usize = 9;
+/// The length of an arrowhead, in arrow widths.
+const ARROWHEAD: usize = 3;
+
+pub struct SvgOptions {
+    /// The side drawn at the bottom.
+    pub bottom: PieceColour,
+    /// Whether the files and ranks are written around the board.
+    pub coordinates: bool,
+    /// Squares tinted yellow, such as those of the last move.
+    pub highlights: Vec<Position>,
+    /// Arrows from one square to another.
+    pub arrows: Vec<(Position, Position)>,
+}
+
+impl Default for SvgOptions {
+    fn default() -> Self {
+        SvgOptions {
+            bottom: PieceColour::White,
+            coordinates: true,
+            highlights: Vec::new(),
+            arrows: Vec::new(),
+        }
+    }
+}
+
+/// `options` with the squares of the last move on `board` highlighted as well.
+pub fn with_last_move(board: &Board, options: &SvgOptions) -> SvgOptions {
+    let mut highlights: Vec<Position> = options.highlights.clone();
+    if let Some(last_move) = board.last_move() {
+        highlights.extend([last_move.from, last_move.to]);
+    }
+    SvgOptions {
+        bottom: options.bottom,
+        coordinates: options.coordinates,
+        highlights,
+        arrows: options.arrows.clone(),
+    }
+}
+
+/// Draws `board` as an SVG document.
+pub fn to_svg(board: &Board, options: &SvgOptions) -> String {
+    let margin: usize = if options.coordinates { MARGIN } else { 0 };
+    let size: usize = 8 * SQUARE + 2 * margin;
+    let mut svg: String = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+         viewBox=\"0 0 {} {}\">\n",
+        size, size, size, size
+    );
+    svg.push_str(&format!(
+        "<defs><marker id=\"arrowhead\" markerWidth=\"{}\" markerHeight=\"{}\" refX=\"0\" \
+         refY=\"{}\" orient=\"auto\"><path d=\"M0,0 L{},{} L0,{} z\" fill=\"{}\"/></marker></defs>\n",
+        ARROWHEAD,
+        ARROWHEAD,
+        ARROWHEAD as f64 / 2.0,
+        ARROWHEAD,
+        ARROWHEAD as f64 / 2.0,
+        ARROWHEAD,
+        ARROW
+    ));
+    if options.coordinates {
+        svg.push_str(&format!(
+            "<rect width=\"{}\" height=\"{}\" fill=\"#ffffff\"/>\n",
+            size, size
+        ));
+    }
+
+    for row in 1..=8 {
+        for column in 1..=8 {
+            let square: Position = Position::new(row, column);
+            let (x, y): (usize, usize) = corner(square, options.bottom, margin);
+            let fill: &str = if (row + column).is_multiple_of(2) {
+                DARK_SQUARE
+            } else {
+                LIGHT_SQUARE
+            };
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>\n",
+                x, y, SQUARE, SQUARE, fill
+            ));
+        }
+    }
+    for square in &options.highlights {
+        let (x, y): (usize, usize) = corner(*square, options.bottom, margin);
+        svg.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" \
+             fill-opacity=\"0.5\"/>\n",
+            x, y, SQUARE, SQUARE, HIGHLIGHT
+        ));
+    }
+
+    if options.coordinates {
+        svg.push_str(&coordinates(options.bottom, margin));
+    }
+
+    svg.push_str(
+        "<g font-family=\"DejaVu Sans, Segoe UI Symbol, serif\" font-size=\"38\" \
+         text-anchor=\"middle\" dominant-baseline=\"central\">\n",
+    );
+    for row in 1..=8 {
+        for column in 1..=8 {
+            let square: Position = Position::new(row, column);
+            let Some(piece) = board.piece_at(square) else {
+                continue;
+            };
+            let (x, y): (usize, usize) = corner(square, options.bottom, margin);
+            let paint: &str = match piece.colour {
+                PieceColour::White => "fill=\"#ffffff\" stroke=\"#000000\" stroke-width=\"1.2\"",
+                PieceColour::Black => "fill=\"#000000\"",
+            };
+            // the variation selector keeps the pawn from turning into an emoji
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" {}>{}\u{FE0E}</text>\n",
+                x + SQUARE / 2,
+                y + SQUARE / 2 + 2,
+                paint,
+                glyph(piece.piece_type)
+            ));
+        }
+    }
+    svg.push_str("</g>\n");
+
+    for (from, to) in &options.arrows {
+        svg.push_str(&arrow(*from, *to, options.bottom, margin));
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The top left corner of `square` in the image.
+fn corner(square: Position, bottom: PieceColour, margin: usize) -> (usize, usize) {
+    let (row_from_top, column_from_left): (usize, usize) = match bottom {
+        PieceColour::White => (8 - square.row, square.column - 1),
+        PieceColour::Black => (square.row - 1, 8 - square.column),
+    };
+    (
+        margin + column_from_left * SQUARE,
+        margin + row_from_top * SQUARE,
+    )
+}
+
+fn coordinates(bottom: PieceColour, margin: usize) -> String {
+    let mut text: String = String::from(
+        "<g font-family=\"sans-serif\" font-size=\"13\" fill=\"#555555\" \
+         text-anchor=\"middle\" dominant-baseline=\"central\">\n",
+    );
+    for index in 1..=8 {
+        let (x, _): (usize, usize) = corner(Position::new(1, index), bottom, margin);
+        let (_, y): (usize, usize) = corner(Position::new(index, 1), bottom, margin);
+        text.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{}</text>\n",
+            x + SQUARE / 2,
+            margin + 8 * SQUARE + margin / 2,
+            column_to_char(index)
+        ));
+        text.push_str(&format!(
+            "<text x=\"{}\" y=\"{}\">{}</text>\n",
+            margin / 2,
+            y + SQUARE / 2,
+            index
+        ));
+    }
+    text.push_str("</g>\n");
+    text
+}
+
+/// An arrow between the centres of two squares, with its head ending on the centre of `to`.
+fn arrow(from: Position, to: Position, bottom: PieceColour, margin: usize) -> String {
+    let centre = |square: Position| -> (f64, f64) {
+        let (x, y): (usize, usize) = corner(square, bottom, margin);
+        ((x + SQUARE / 2) as f64, (y + SQUARE / 2) as f64)
+    };
+    let (x1, y1): (f64, f64) = centre(from);
+    let (x2, y2): (f64, f64) = centre(to);
+    let length: f64 = (x2 - x1).hypot(y2 - y1);
+    if length == 0.0 {
+        return String::new();
+    }
+    let head: f64 = (ARROWHEAD * ARROW_WIDTH) as f64;
+    let (end_x, end_y): (f64, f64) = (
+        x2 - (x2 - x1) * head / length,
+        y2 - (y2 - y1) * head / length,
+    );
+    format!(
+        "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke=\"{}\" \
+         stroke-width=\"{}\" stroke-opacity=\"0.8\" marker-end=\"url(#arrowhead)\"/>\n",
+        x1, y1, end_x, end_y, ARROW, ARROW_WIDTH
+    )
+}
+
+/// The solid glyph of each piece type, filled white or black to tell the sides apart.
+fn glyph(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::King => '♚',
+        PieceType::Queen => '♛',
+        PieceType::Rook => '♜',
+        PieceType::Bishop => '♝',
+        PieceType::Knight => '♞',
+        PieceType::Pawn => '♟',
+    }
+}
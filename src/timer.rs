@@ -1,4 +1,3 @@
-// use std::process;
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread::{self, JoinHandle};
 use std::time::{Duration, Instant};
@@ -22,16 +21,20 @@ impl Timer {
     }
 
     pub fn remaining_duration(&self) -> Duration {
-        self.start.lock().unwrap().clone()
+        *self.start.lock().unwrap()
+    }
+
+    /// Overwrites the time left. The countdown thread stops once a clock runs out, so a clock
+    /// given time again after that needs `countdown_start` to run.
+    pub fn set_remaining(&self, remaining: Duration) {
+        *self.start.lock().unwrap() = remaining;
     }
 
-    #[allow(dead_code)]
     pub fn is_running(&self) -> bool {
         !self.is_finished() && !*self.paused.lock().unwrap()
     }
 
     pub fn countdown_start(&self) -> JoinHandle<()> {
-        // let secs: u64 = *self.start.lock().unwrap();
         let duration: Arc<Mutex<Duration>> = Arc::clone(&self.start);
         let paused: Arc<Mutex<bool>> = Arc::clone(&self.paused);
         let control: Arc<Condvar> = Arc::clone(&self.control);
@@ -59,7 +62,7 @@ impl Timer {
 
     fn countdown(duration: Arc<Mutex<Duration>>, paused: Arc<Mutex<bool>>, control: Arc<Condvar>) {
         const PRECISION: Duration = Duration::from_millis(20);
-        let control: &Condvar = &*control;
+        let control: &Condvar = &control;
         let mut last_frame_time: Instant = Instant::now();
         loop {
             let mut paused: std::sync::MutexGuard<'_, bool> = paused.lock().unwrap();
@@ -0,0 +1,616 @@
+//! The full-screen terminal interface started with `--tui`, built with the `tui` feature. The
+//! terminal is put in raw mode so every key and mouse click arrives as it happens: the arrow keys
+//! and Enter (or a click) pick a piece and then where it goes, and anything typed goes on the
+//! input line at the bottom and is read like a line typed in the plain interface. All drawing
+//! goes through one `Screen`, which puts the cursor back at the end of the input line after
+//! every update, so the clocks ticking over never get mixed into what is being typed.
+//!
+//! ```text
+//!  Black 9:58.1                  White has taken
+//! 8 ♖  ♘  ♗  ♕  ♔  ♗  ♘  ♖       ♙
+//! ...                            Black has taken
+//! 1 ♜  ♞  ♝  ♛  ♚  ♝  ♞  ♜
+//!    a  b  c  d  e  f  g  h      Moves
+//!  White 9:59.2                  1. e4 d5
+//!                                2. exd5
+//! opponent played exd5
+//! <draw offers>
+//! <status>
+//! your move: _
+//! ```
+
+use crate::{
+    board::Board,
+    pgn::MoveRecord,
+    piece::{PieceColour, PieceType},
+    player::{self, Input, Interrupt, Notification, Player, PlayerAction},
+    position::{column_to_char, Position},
+    render::{self, Frame, Line, Orientation, Renderer, SharedRenderer, ESC},
+    style::Style,
+};
+use crossterm::event::{
+    self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use crossterm::{execute, terminal};
+use std::io::{self, Write};
+use std::mem;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+const MINIMUM_WIDTH: u16 = 50;
+const MINIMUM_HEIGHT: u16 = 16;
+/// The first screen row and column of the squares, each of which is three columns wide.
+const BOARD_TOP: u16 = 2;
+const BOARD_LEFT: u16 = 3;
+/// The captured pieces and the move list fill the screen from this column rightwards.
+const PANE_LEFT: u16 = 31;
+const MOVES_TOP: u16 = 7;
+const LIGHT_SQUARE: &str = "107";
+const DARK_SQUARE: &str = "47";
+const CURSOR_BACKGROUND: &str = "46";
+const SELECTED_BACKGROUND: &str = "45";
+
+/// The terminal in full-screen mode, shared by the renderer and every player sitting at it.
+pub struct Tui {
+    screen: Arc<Mutex<Screen>>,
+    lines: Arc<Mutex<Receiver<String>>>,
+}
+
+impl Tui {
+    /// Switches the terminal to raw mode on the alternate screen and starts reading keys and
+    /// mouse clicks. The terminal is given back by the renderer's `close`.
+    pub fn start(orientation: Orientation, style: Style) -> io::Result<Self> {
+        let (width, height): (u16, u16) = terminal::size()?;
+        terminal::enable_raw_mode()?;
+        let mut output: io::Stdout = io::stdout();
+        execute!(output, terminal::EnterAlternateScreen, EnableMouseCapture)?;
+
+        let screen: Arc<Mutex<Screen>> = Arc::new(Mutex::new(Screen::new(
+            output,
+            orientation,
+            style,
+            width,
+            height,
+        )));
+        let (sender, receiver): (Sender<String>, Receiver<String>) = mpsc::channel();
+        let reader_screen: Arc<Mutex<Screen>> = Arc::clone(&screen);
+        thread::spawn(move || read_events(reader_screen, sender));
+        Ok(Tui {
+            screen,
+            lines: Arc::new(Mutex::new(receiver)),
+        })
+    }
+
+    pub fn renderer(&self) -> SharedRenderer {
+        self.screen.clone()
+    }
+
+    pub fn player(&self) -> TuiPlayer {
+        TuiPlayer {
+            screen: Arc::clone(&self.screen),
+            lines: Arc::clone(&self.lines),
+            interrupt: Interrupt::default(),
+        }
+    }
+}
+
+/// Handles keys, clicks and resizes until the terminal goes away or Ctrl-C is pressed, passing
+/// every line the player submits on to whichever `TuiPlayer` is asking for a move. Stopping
+/// closes the channel, which the players take as leaving the game.
+fn read_events(screen: Arc<Mutex<Screen>>, sender: Sender<String>) {
+    while let Ok(event) = event::read() {
+        let mut screen = screen.lock().unwrap();
+        let submitted: Option<String> = match event {
+            Event::Key(key)
+                if key.modifiers.contains(KeyModifiers::CONTROL)
+                    && key.code == KeyCode::Char('c') =>
+            {
+                screen.line(Line::Status, "Interrupted, leaving the game");
+                break;
+            }
+            Event::Key(key) if key.kind != KeyEventKind::Release => screen.key(key),
+            Event::Mouse(mouse) => screen.mouse(mouse),
+            Event::Resize(width, height) => {
+                screen.width = width;
+                screen.height = height;
+                screen.draw();
+                None
+            }
+            _ => None,
+        };
+        drop(screen);
+        if let Some(line) = submitted {
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+/// Everything on the screen, kept so any part of it can be redrawn on its own.
+struct Screen {
+    output: io::Stdout,
+    orientation: Orientation,
+    /// Only the piece set applies, as the squares are drawn in colour.
+    style: Style,
+    board: Board,
+    turn: PieceColour,
+    clocks: Option<(Duration, Duration)>,
+    /// Squares asked for with "moves <square>".
+    destinations: Vec<Position>,
+    moves: Vec<MoveRecord>,
+    /// The variant's notes for (white, black), shown after the clocks.
+    notes: (String, String),
+    prompt: String,
+    opponent_move: String,
+    draw_offer: String,
+    status: String,
+    input: String,
+    cursor: Position,
+    selected: Option<Position>,
+    /// The side a `TuiPlayer` is waiting on; pieces can only be picked while it is set.
+    awaiting: Option<PieceColour>,
+    promoting: bool,
+    width: u16,
+    height: u16,
+    open: bool,
+}
+
+impl Screen {
+    fn new(
+        output: io::Stdout,
+        orientation: Orientation,
+        style: Style,
+        width: u16,
+        height: u16,
+    ) -> Self {
+        Screen {
+            output,
+            orientation,
+            style,
+            board: Board::new(),
+            turn: PieceColour::White,
+            clocks: None,
+            destinations: Vec::new(),
+            moves: Vec::new(),
+            notes: (String::new(), String::new()),
+            prompt: String::new(),
+            opponent_move: String::new(),
+            draw_offer: String::new(),
+            status: String::new(),
+            input: String::new(),
+            // in front of the king of the side at the bottom
+            cursor: match orientation.bottom(PieceColour::White) {
+                PieceColour::White => Position::new(2, 5),
+                PieceColour::Black => Position::new(7, 5),
+            },
+            selected: None,
+            awaiting: None,
+            promoting: false,
+            width,
+            height,
+            open: true,
+        }
+    }
+
+    fn bottom(&self) -> PieceColour {
+        self.orientation.bottom(self.turn)
+    }
+
+    fn too_small(&self) -> bool {
+        self.width < MINIMUM_WIDTH || self.height < MINIMUM_HEIGHT
+    }
+
+    /// The square drawn `row` squares from the top and `column` squares from the left.
+    fn square_at(&self, row: u16, column: u16) -> Position {
+        let (row, column): (usize, usize) = (row as usize, column as usize);
+        match self.bottom() {
+            PieceColour::White => Position::new(8 - row, column + 1),
+            PieceColour::Black => Position::new(row + 1, 8 - column),
+        }
+    }
+
+    /// The square under a screen cell, counting rows and columns from 1.
+    fn square_under(&self, row: u16, column: u16) -> Option<Position> {
+        if !(BOARD_TOP..BOARD_TOP + 8).contains(&row)
+            || !(BOARD_LEFT..BOARD_LEFT + 24).contains(&column)
+        {
+            return None;
+        }
+        Some(self.square_at(row - BOARD_TOP, (column - BOARD_LEFT) / 3))
+    }
+
+    fn write(&mut self, text: &str) {
+        if !self.open {
+            return;
+        }
+        let _ = self.output.write_all(text.as_bytes());
+        let _ = self.output.flush();
+    }
+
+    /// Redraws the whole screen, as after a move or a resize.
+    fn draw(&mut self) {
+        let mut text: String = format!("{}2J", ESC);
+        if self.too_small() {
+            text.push_str(&format!(
+                "{}1;1HMake the terminal at least {}x{}",
+                ESC, MINIMUM_WIDTH, MINIMUM_HEIGHT
+            ));
+            self.write(&text);
+            return;
+        }
+        text.push_str(&self.clock_text(self.bottom().opponent()));
+        text.push_str(&self.board_text());
+        text.push_str(&self.clock_text(self.bottom()));
+        text.push_str(&self.pane_text());
+        for line in [Line::OpponentMove, Line::DrawOffer, Line::Status] {
+            text.push_str(&self.line_text(line));
+        }
+        text.push_str(&self.input_text());
+        self.write(&text);
+    }
+
+    /// Redraws the board and the input line, after the cursor, selection or input changed.
+    fn refresh(&mut self) {
+        if self.too_small() {
+            return;
+        }
+        let text: String = self.board_text() + &self.input_text();
+        self.write(&text);
+    }
+
+    fn clock_text(&self, colour: PieceColour) -> String {
+        let row: u16 = if colour == self.bottom() {
+            BOARD_TOP + 9
+        } else {
+            1
+        };
+        let mut clock: String = match self.clocks {
+            Some(clocks) => format!(
+                " {} {}",
+                render::colour_name(colour),
+                render::format_clock(render::clock_of(clocks, colour))
+            ),
+            None => String::new(),
+        };
+        let note: &str = match colour {
+            PieceColour::White => &self.notes.0,
+            PieceColour::Black => &self.notes.1,
+        };
+        if !note.is_empty() {
+            if clock.is_empty() {
+                clock = format!(" {}", render::colour_name(colour));
+            }
+            clock.push_str(&format!("  {}", note));
+        }
+        format!(
+            "{}{};1H{:<width$}",
+            ESC,
+            row,
+            clock,
+            width = PANE_LEFT as usize - 1
+        )
+    }
+
+    fn board_text(&self) -> String {
+        let destinations: Vec<Position> = match (self.selected, self.awaiting) {
+            (Some(from), Some(colour)) => self.board.destinations(colour, from),
+            _ => self.destinations.clone(),
+        };
+        let highlights: Vec<(Position, &str)> = render::highlights(&Frame {
+            board: &self.board,
+            turn: self.turn,
+            clocks: self.clocks,
+            destinations: &destinations,
+            moves: &self.moves,
+            notes: self.notes.clone(),
+        });
+
+        let mut text: String = String::new();
+        for row in 0..8 {
+            let rank: usize = self.square_at(row, 0).row;
+            text.push_str(&format!("{}{};1H{} ", ESC, BOARD_TOP + row, rank));
+            for column in 0..8 {
+                let square: Position = self.square_at(row, column);
+                let background: &str = if self.awaiting.is_some() && square == self.cursor {
+                    CURSOR_BACKGROUND
+                } else if Some(square) == self.selected {
+                    SELECTED_BACKGROUND
+                } else if let Some((_, background)) = highlights
+                    .iter()
+                    .rev()
+                    .find(|(highlighted, _)| *highlighted == square)
+                {
+                    background
+                } else if (square.row + square.column).is_multiple_of(2) {
+                    DARK_SQUARE
+                } else {
+                    LIGHT_SQUARE
+                };
+                let symbol: char = self
+                    .board
+                    .piece_at(square)
+                    .map_or(' ', |p| self.style.pieces.symbol(p.piece_type, p.colour));
+                text.push_str(&format!("{}{};30m {} {}0m", ESC, background, symbol, ESC));
+            }
+        }
+        text.push_str(&format!("{}{};1H  ", ESC, BOARD_TOP + 8));
+        for column in 0..8 {
+            let letter: char = column_to_char(self.square_at(0, column).column);
+            text.push_str(&format!(" {} ", letter));
+        }
+        text
+    }
+
+    /// The captured pieces and as much of the end of the move list as fits.
+    fn pane_text(&self) -> String {
+        let mut lines: Vec<String> = vec![
+            String::from("White has taken"),
+            render::captures(&self.board, PieceColour::White, &self.style),
+            String::from("Black has taken"),
+            render::captures(&self.board, PieceColour::Black, &self.style),
+            String::new(),
+            String::from("Moves"),
+        ];
+        let rows: usize = (self.height - 3).saturating_sub(MOVES_TOP) as usize;
+        let numbered: Vec<String> = self
+            .moves
+            .chunks(2)
+            .enumerate()
+            .map(|(i, pair)| {
+                let sans: Vec<&str> = pair.iter().map(|record| record.san.as_str()).collect();
+                format!("{}. {}", i + 1, sans.join(" "))
+            })
+            .collect();
+        lines.extend(numbered.into_iter().rev().take(rows).rev());
+
+        let width: usize = (self.width - PANE_LEFT + 1) as usize;
+        let mut text: String = String::new();
+        for (i, line) in lines.iter().enumerate() {
+            let shown: String = line.chars().take(width).collect();
+            text.push_str(&format!("{}{};{}H{}{}K", ESC, i + 1, PANE_LEFT, shown, ESC));
+        }
+        text
+    }
+
+    fn line_text(&self, line: Line) -> String {
+        let (row, text): (u16, &str) = match line {
+            Line::OpponentMove => (self.height - 3, &self.opponent_move),
+            Line::DrawOffer => (self.height - 2, &self.draw_offer),
+            Line::Status => (self.height - 1, &self.status),
+            Line::Prompt => return self.input_text(),
+        };
+        let shown: String = text.chars().take(self.width as usize).collect();
+        format!("{}{};1H{}2K{}", ESC, row, ESC, shown)
+    }
+
+    /// The prompt and what has been typed so far, leaving the cursor after it.
+    fn input_text(&self) -> String {
+        format!(
+            "{}{};1H{}2K{} {}",
+            ESC, self.height, ESC, self.prompt, self.input
+        )
+    }
+
+    fn key(&mut self, key: KeyEvent) -> Option<String> {
+        if self.promoting {
+            return match key.code {
+                KeyCode::Char(letter) if "qrbnk".contains(letter.to_ascii_lowercase()) => {
+                    Some(letter.to_string())
+                }
+                _ => None,
+            };
+        }
+
+        let mut submitted: Option<String> = None;
+        match key.code {
+            KeyCode::Up => self.move_cursor(1, 0),
+            KeyCode::Down => self.move_cursor(-1, 0),
+            KeyCode::Left => self.move_cursor(0, -1),
+            KeyCode::Right => self.move_cursor(0, 1),
+            KeyCode::Enter if !self.input.is_empty() => {
+                self.awaiting = None;
+                submitted = Some(mem::take(&mut self.input));
+            }
+            KeyCode::Enter => submitted = self.select(self.cursor),
+            KeyCode::Char(' ') if self.input.is_empty() => submitted = self.select(self.cursor),
+            KeyCode::Esc => {
+                self.selected = None;
+                self.input.clear();
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Char(character) => self.input.push(character),
+            _ => return None,
+        }
+        self.refresh();
+        submitted
+    }
+
+    fn mouse(&mut self, mouse: MouseEvent) -> Option<String> {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) || self.too_small() {
+            return None;
+        }
+        // crossterm counts from 0, the escape codes from 1
+        let square: Position = self.square_under(mouse.row + 1, mouse.column + 1)?;
+        self.cursor = square;
+        let submitted: Option<String> = self.select(square);
+        self.refresh();
+        submitted
+    }
+
+    /// Moves the cursor by whole squares, up and right as seen on the screen.
+    fn move_cursor(&mut self, up: i32, right: i32) {
+        let (up, right): (i32, i32) = match self.bottom() {
+            PieceColour::White => (up, right),
+            PieceColour::Black => (-up, -right),
+        };
+        self.cursor = Position::new(
+            (self.cursor.row as i32 + up).clamp(1, 8) as usize,
+            (self.cursor.column as i32 + right).clamp(1, 8) as usize,
+        );
+    }
+
+    /// Picks up the piece on `square`, or plays the piece already picked up to `square`. A move
+    /// comes back in coordinate notation, offering a draw if "draw" has been typed.
+    fn select(&mut self, square: Position) -> Option<String> {
+        let colour: PieceColour = self.awaiting?;
+        if let Some(from) = self.selected.take() {
+            if self.board.destinations(colour, from).contains(&square) {
+                let mut notation: String = format!("{}{}", from, square);
+                if matches!(self.input.trim(), "draw" | "(=)") {
+                    notation.push_str(" draw");
+                    self.input.clear();
+                }
+                self.awaiting = None;
+                return Some(notation);
+            }
+        }
+        if self
+            .board
+            .piece_at(square)
+            .is_some_and(|piece| piece.colour == colour)
+            && !self.board.destinations(colour, square).is_empty()
+        {
+            self.selected = Some(square);
+        }
+        None
+    }
+}
+
+impl Renderer for Screen {
+    fn frame(&mut self, frame: &Frame) {
+        self.board = frame.board.clone();
+        self.turn = frame.turn;
+        self.clocks = frame.clocks;
+        self.destinations = frame.destinations.to_vec();
+        self.moves = frame.moves.to_vec();
+        self.notes = frame.notes.clone();
+        self.selected = None;
+        self.opponent_move.clear();
+        self.draw_offer.clear();
+        self.status.clear();
+        self.draw();
+    }
+
+    fn tick(&mut self, colour: PieceColour, remaining: Duration) {
+        self.clocks = self.clocks.map(|(white, black)| match colour {
+            PieceColour::White => (remaining, black),
+            PieceColour::Black => (white, remaining),
+        });
+        if self.too_small() {
+            return;
+        }
+        let text: String = self.clock_text(colour) + &self.input_text();
+        self.write(&text);
+    }
+
+    fn line(&mut self, line: Line, text: &str) {
+        let shown: &mut String = match line {
+            Line::Prompt => &mut self.prompt,
+            Line::OpponentMove => &mut self.opponent_move,
+            Line::DrawOffer => &mut self.draw_offer,
+            Line::Status => &mut self.status,
+        };
+        *shown = text.to_string();
+        if self.too_small() {
+            return;
+        }
+        let text: String = self.line_text(line) + &self.input_text();
+        self.write(&text);
+    }
+
+    /// Leaves the alternate screen and repeats the last status line, usually the result, on the
+    /// normal one.
+    fn close(&mut self) {
+        if !self.open {
+            return;
+        }
+        self.open = false;
+        let _ = execute!(
+            self.output,
+            DisableMouseCapture,
+            terminal::LeaveAlternateScreen
+        );
+        let _ = terminal::disable_raw_mode();
+        if !self.status.is_empty() {
+            let _ = writeln!(self.output, "{}", self.status);
+        }
+    }
+}
+
+/// Someone at the terminal, playing with the keyboard and mouse.
+pub struct TuiPlayer {
+    screen: Arc<Mutex<Screen>>,
+    lines: Arc<Mutex<Receiver<String>>>,
+    interrupt: Interrupt,
+}
+
+impl TuiPlayer {
+    fn show(&self, line: Line, text: &str) {
+        self.screen.lock().unwrap().line(line, text);
+    }
+
+    /// The next line submitted at the terminal.
+    fn next_line(&self) -> Input<String> {
+        self.interrupt.recv(&self.lines.lock().unwrap())
+    }
+}
+
+impl Player for TuiPlayer {
+    fn request_move(&mut self, _board: &Board, colour: PieceColour) -> PlayerAction {
+        {
+            let mut screen = self.screen.lock().unwrap();
+            screen.awaiting = Some(colour);
+            screen.line(Line::Prompt, "your move:");
+        }
+        let line: Input<String> = self.next_line();
+        {
+            let mut screen = self.screen.lock().unwrap();
+            screen.awaiting = None;
+            screen.line(Line::Prompt, "");
+        }
+        match line {
+            Input::Received(line) => PlayerAction::parse(&line),
+            // Ctrl-C, or the terminal went away
+            Input::Closed => PlayerAction::Abandon,
+            Input::Interrupted => PlayerAction::Interrupted,
+        }
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        {
+            let mut screen = self.screen.lock().unwrap();
+            screen.promoting = true;
+            screen.line(Line::Prompt, "promote to (q, r, b, n):");
+        }
+        let piece_type: PieceType = loop {
+            let line: String = match self.next_line() {
+                Input::Received(line) => line,
+                Input::Closed | Input::Interrupted => String::new(),
+            };
+            match player::promotion_choice(&line) {
+                Some(piece_type) => break piece_type,
+                None => self.show(Line::Status, "Invalid piece symbol"),
+            }
+        };
+        let mut screen = self.screen.lock().unwrap();
+        screen.promoting = false;
+        screen.line(Line::Prompt, "");
+        piece_type
+    }
+
+    fn notify(&mut self, notification: &Notification) {
+        if let Some((line, text)) = player::notification_line(notification) {
+            self.show(line, &text);
+        }
+    }
+
+    fn interrupt(&self) -> Option<Interrupt> {
+        Some(self.interrupt.clone())
+    }
+}
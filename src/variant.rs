@@ -0,0 +1,243 @@
+//! Variants that keep the moves of standard chess but add ways to win or, like Crazyhouse and
+//! Atomic, set the board up with rules of their own. `GameManager` asks its variant after every
+//! move whether the side that moved has won, before looking for mate, stalemate and draws.
+
+use crate::{
+    board::Board,
+    piece::{PieceColour, PieceType},
+    position::{NotationError, Position},
+    result::Termination,
+};
+
+/// The name of standard chess, which has no `Variant` tag in PGN.
+pub const STANDARD: &str = "Standard";
+
+const CHECKS_TO_WIN: u32 = 3;
+
+pub trait Variant: Send {
+    /// The name written in PGN `Variant` tags and save files.
+    fn name(&self) -> &'static str;
+
+    fn is_standard(&self) -> bool {
+        self.name() == STANDARD
+    }
+
+    /// Forgets anything kept track of in an earlier game, as before the first move.
+    fn start(&mut self) {}
+
+    /// Turns on any rules the variant needs on `board`, such as Crazyhouse pockets.
+    fn set_up(&self, _board: &mut Board) {}
+
+    /// Called once `colour` has played a move, with the board after it. Returns how `colour`
+    /// won if the move wins by this variant's own rules.
+    fn after_move(&mut self, _board: &Board, _colour: PieceColour) -> Option<Termination> {
+        None
+    }
+
+    /// Whether the game is drawn for lack of material, checked after every move.
+    fn insufficient_material(&self, board: &Board) -> bool {
+        board.is_insufficient_material()
+    }
+
+    /// Shown beside `colour`'s clock, such as the checks it has given; empty for nothing.
+    fn note(&self, _board: &Board, _colour: PieceColour) -> String {
+        String::new()
+    }
+
+    /// The FEN of `board`, with any fields the variant adds for its own state.
+    fn to_fen(&self, board: &Board, turn: PieceColour, move_number: usize) -> String {
+        board.to_fen(turn, move_number)
+    }
+
+    /// Reads a FEN written by `to_fen`, taking the variant's own state from it.
+    fn read_fen(&mut self, fen: &str) -> Result<(Board, PieceColour), NotationError> {
+        let (mut board, turn): (Board, PieceColour) = Board::from_fen(fen)?;
+        self.set_up(&mut board);
+        Ok((board, turn))
+    }
+}
+
+/// The variant called `name`, as in a PGN `Variant` tag or written in lower case with dashes
+/// ("king-of-the-hill", "three-check").
+pub fn from_name(name: &str) -> Option<Box<dyn Variant>> {
+    let name: String = name
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match name.as_str() {
+        "standard" | "chess" => Some(Box::new(Standard)),
+        "kingofthehill" | "koth" => Some(Box::new(KingOfTheHill)),
+        "threecheck" | "3check" => Some(Box::new(ThreeCheck::new())),
+        "crazyhouse" | "zh" => Some(Box::new(Crazyhouse)),
+        "atomic" => Some(Box::new(Atomic)),
+        _ => None,
+    }
+}
+
+pub struct Standard;
+
+impl Variant for Standard {
+    fn name(&self) -> &'static str {
+        STANDARD
+    }
+}
+
+/// A king reaching one of the four centre squares wins.
+pub struct KingOfTheHill;
+
+impl Variant for KingOfTheHill {
+    fn name(&self) -> &'static str {
+        "King of the Hill"
+    }
+
+    fn after_move(&mut self, board: &Board, colour: PieceColour) -> Option<Termination> {
+        let on_the_hill: bool = ["d4", "e4", "d5", "e5"].iter().any(|notation| {
+            let square: Position = Position::from_notation(notation).unwrap();
+            board
+                .piece_at(square)
+                .is_some_and(|piece| piece.piece_type == PieceType::King && piece.colour == colour)
+        });
+        on_the_hill.then_some(Termination::CentreReached)
+    }
+
+    /// A lone king can still walk to the centre.
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+}
+
+/// Giving check for the third time wins. The checks left for each side are kept in FEN as an
+/// extra field after the en passant square, "3+3" at the start.
+pub struct ThreeCheck {
+    /// The checks given so far, as (white, black).
+    checks: (u32, u32),
+}
+
+impl Default for ThreeCheck {
+    fn default() -> Self {
+        ThreeCheck::new()
+    }
+}
+
+impl ThreeCheck {
+    pub fn new() -> Self {
+        ThreeCheck { checks: (0, 0) }
+    }
+
+    pub fn checks(&self, colour: PieceColour) -> u32 {
+        match colour {
+            PieceColour::White => self.checks.0,
+            PieceColour::Black => self.checks.1,
+        }
+    }
+}
+
+impl Variant for ThreeCheck {
+    fn name(&self) -> &'static str {
+        "Three-check"
+    }
+
+    fn start(&mut self) {
+        self.checks = (0, 0);
+    }
+
+    fn after_move(&mut self, board: &Board, colour: PieceColour) -> Option<Termination> {
+        board.checked_king(colour.opponent())?;
+        let checks: &mut u32 = match colour {
+            PieceColour::White => &mut self.checks.0,
+            PieceColour::Black => &mut self.checks.1,
+        };
+        *checks += 1;
+        (*checks >= CHECKS_TO_WIN).then_some(Termination::ThreeChecks)
+    }
+
+    /// A single bishop or knight can still give the checks that win.
+    fn insufficient_material(&self, _board: &Board) -> bool {
+        false
+    }
+
+    fn note(&self, _board: &Board, colour: PieceColour) -> String {
+        match self.checks(colour) {
+            1 => String::from("1 check"),
+            checks => format!("{} checks", checks),
+        }
+    }
+
+    fn to_fen(&self, board: &Board, turn: PieceColour, move_number: usize) -> String {
+        let mut fields: Vec<String> = board
+            .to_fen(turn, move_number)
+            .split(' ')
+            .map(str::to_string)
+            .collect();
+        let left = |colour: PieceColour| CHECKS_TO_WIN.saturating_sub(self.checks(colour));
+        fields.insert(
+            4,
+            format!("{}+{}", left(PieceColour::White), left(PieceColour::Black)),
+        );
+        fields.join(" ")
+    }
+
+    /// A FEN without the checks field starts both sides on no checks.
+    fn read_fen(&mut self, fen: &str) -> Result<(Board, PieceColour), NotationError> {
+        let mut fields: Vec<&str> = fen.split_whitespace().collect();
+        self.start();
+        if let Some(index) = fields.iter().position(|field| field.contains('+')) {
+            let (white, black): (&str, &str) = fields[index].split_once('+').unwrap();
+            let given = |left: &str| -> Result<u32, NotationError> {
+                let left: u32 = left.parse().map_err(|_| NotationError)?;
+                CHECKS_TO_WIN.checked_sub(left).ok_or(NotationError)
+            };
+            self.checks = (given(white)?, given(black)?);
+            fields.remove(index);
+        }
+        Board::from_fen(&fields.join(" "))
+    }
+}
+
+/// Captured pieces go to the capturer's pocket, and instead of moving a side can drop one of
+/// them on an empty square as its own ("N@f3"). A promoted piece goes back as a pawn. The
+/// pockets follow the placement in FEN, as in `.../RNBQKBNR[Qp]`, with "~" after promoted
+/// pieces.
+pub struct Crazyhouse;
+
+impl Variant for Crazyhouse {
+    fn name(&self) -> &'static str {
+        "Crazyhouse"
+    }
+
+    fn set_up(&self, board: &mut Board) {
+        board.use_pockets();
+    }
+
+    fn note(&self, board: &Board, colour: PieceColour) -> String {
+        let pocket: &[PieceType] = board.pocket(colour);
+        if pocket.is_empty() {
+            return String::new();
+        }
+        let letters: Vec<String> = pocket
+            .iter()
+            .map(|piece_type| piece_type.letter().to_string())
+            .collect();
+        format!("pocket {}", letters.join(" "))
+    }
+}
+
+/// Every capture explodes, taking the capturing piece and all pieces but pawns next to the
+/// capture square off the board. Blowing up the enemy king wins; kings cannot capture, and
+/// kings side by side do not give check.
+pub struct Atomic;
+
+impl Variant for Atomic {
+    fn name(&self) -> &'static str {
+        "Atomic"
+    }
+
+    fn set_up(&self, board: &mut Board) {
+        board.use_explosions();
+    }
+
+    fn after_move(&mut self, board: &Board, colour: PieceColour) -> Option<Termination> {
+        (!board.has_king(colour.opponent())).then_some(Termination::KingExploded)
+    }
+}
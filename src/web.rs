@@ -0,0 +1,449 @@
+//! Browser front end. `--white web` or `--black web` plays that side from a page served on
+//! `--web <port>` (default 8080) instead of the terminal.
+//!
+//! The page long-polls `GET /state?since=<version>`, which answers as soon as anything has
+//! changed since `version` (or after `POLL_TIMEOUT` regardless) with the position, moves,
+//! clocks and any message as JSON. Moves, `resign` and `accept` are sent as the body of
+//! `POST /move` in the same words a player would type, so they go through the same checks as
+//! moves typed into the terminal.
+
+use crate::{
+    board::Board,
+    game::GameManager,
+    pgn::MoveRecord,
+    piece::{PieceColour, PieceType},
+    player::{self, Input, Interrupt, Notification, Player, PlayerAction},
+    position::CoordinateMove,
+    result::Termination,
+    timer::Timer,
+};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_PORT: u16 = 8080;
+const PAGE: &str = include_str!("web.html");
+const POLL_TIMEOUT: Duration = Duration::from_secs(25);
+const LINGER_TIMEOUT: Duration = Duration::from_secs(2);
+const MAX_BODY: usize = 1024;
+
+/// What the page shows, kept up to date by the web players. `version` goes up with every
+/// change and `seen` is the latest version a page has been sent.
+struct WebState {
+    version: u64,
+    seen: u64,
+    board: Board,
+    turn: PieceColour,
+    moves: Vec<MoveRecord>,
+    draw_offer: Option<PieceColour>,
+    controlled: Vec<PieceColour>,
+    awaiting: Option<PieceColour>,
+    message: String,
+    result: Option<String>,
+    clocks: Option<(Arc<Timer>, Arc<Timer>)>,
+    inputs: Vec<(PieceColour, Sender<String>)>,
+}
+
+type Shared = Arc<(Mutex<WebState>, Condvar)>;
+
+#[derive(Clone)]
+pub struct WebServer {
+    shared: Shared,
+}
+
+/// A side played from the browser.
+pub struct WebPlayer {
+    server: WebServer,
+    colour: PieceColour,
+    input: Receiver<String>,
+    interrupt: Interrupt,
+    promotion: PieceType,
+}
+
+impl WebServer {
+    pub fn start(port: u16) -> io::Result<Self> {
+        let listener: TcpListener = TcpListener::bind(("127.0.0.1", port))?;
+
+        let shared: Shared = Arc::new((
+            Mutex::new(WebState {
+                version: 1,
+                seen: 0,
+                board: Board::new(),
+                turn: PieceColour::White,
+                moves: Vec::new(),
+                draw_offer: None,
+                controlled: Vec::new(),
+                awaiting: None,
+                message: String::new(),
+                result: None,
+                clocks: None,
+                inputs: Vec::new(),
+            }),
+            Condvar::new(),
+        ));
+        let server: WebServer = WebServer { shared };
+        let accepting: WebServer = server.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let server: WebServer = accepting.clone();
+                thread::spawn(move || {
+                    // a browser that goes away mid-request needs no further handling
+                    let _ = server.serve(stream);
+                });
+            }
+        });
+        Ok(server)
+    }
+
+    pub fn player(&self, colour: PieceColour) -> WebPlayer {
+        let (sender, input): (Sender<String>, Receiver<String>) = mpsc::channel();
+        let mut state = self.shared.0.lock().unwrap();
+        state.controlled.push(colour);
+        state.inputs.push((colour, sender));
+        WebPlayer {
+            server: self.clone(),
+            colour,
+            input,
+            interrupt: Interrupt::default(),
+            promotion: PieceType::Queen,
+        }
+    }
+
+    /// Starts the page from the game's position and shows its clocks, including a flag fall.
+    pub fn attach(&self, game: &mut GameManager) -> io::Result<()> {
+        let replay = game.saved_game().replay()?;
+        self.update(|state| {
+            state.board = replay.board;
+            state.turn = replay.turn;
+            state.moves = replay.moves;
+            state.draw_offer = game.draw_offer();
+            state.clocks = Some(game.clocks());
+        });
+
+        let server: WebServer = self.clone();
+        game.on_flag(Box::new(move |colour| {
+            server.update(|state| {
+                state.awaiting = None;
+                state.result = Some(format!("{} ran out of time", title_case(colour)));
+            });
+        }));
+        Ok(())
+    }
+
+    /// Gives the page a moment to fetch the final position before the program exits.
+    pub fn linger(&self) {
+        let (lock, changed) = &*self.shared;
+        let deadline: Instant = Instant::now() + LINGER_TIMEOUT;
+        let mut state = lock.lock().unwrap();
+        while state.seen < state.version {
+            let now: Instant = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+    }
+
+    fn update(&self, change: impl FnOnce(&mut WebState)) {
+        let (lock, changed) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        change(&mut state);
+        state.version += 1;
+        changed.notify_all();
+    }
+
+    /// Answers a single request and closes the connection.
+    fn serve(&self, stream: TcpStream) -> io::Result<()> {
+        let mut reader: BufReader<TcpStream> = BufReader::new(stream.try_clone()?);
+        let mut request_line: String = String::new();
+        reader.read_line(&mut request_line)?;
+
+        let mut content_length: usize = 0;
+        loop {
+            let mut header: String = String::new();
+            if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.trim().eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body: Vec<u8> = vec![0; content_length.min(MAX_BODY)];
+        reader.read_exact(&mut body)?;
+
+        let mut words = request_line.split_whitespace();
+        let (method, target): (&str, &str) = (
+            words.next().unwrap_or_default(),
+            words.next().unwrap_or_default(),
+        );
+        let (path, query): (&str, &str) = target.split_once('?').unwrap_or((target, ""));
+        match (method, path) {
+            ("GET", "/") => respond(stream, "200 OK", "text/html; charset=utf-8", PAGE),
+            ("GET", "/state") => {
+                let since: u64 = query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("since="))
+                    .and_then(|since| since.parse().ok())
+                    .unwrap_or(0);
+                let json: String = self.wait_for_state(since);
+                respond(stream, "200 OK", "application/json", &json)
+            }
+            ("POST", "/move") => match self.submit(String::from_utf8_lossy(&body).trim()) {
+                Ok(()) => respond(stream, "204 No Content", "text/plain", ""),
+                Err(reason) => respond(stream, "409 Conflict", "text/plain", reason),
+            },
+            _ => respond(stream, "404 Not Found", "text/plain", "not found"),
+        }
+    }
+
+    fn wait_for_state(&self, since: u64) -> String {
+        let (lock, changed) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        let deadline: Instant = Instant::now() + POLL_TIMEOUT;
+        while state.version <= since {
+            let now: Instant = Instant::now();
+            if now >= deadline {
+                break;
+            }
+            state = changed.wait_timeout(state, deadline - now).unwrap().0;
+        }
+        state.seen = state.seen.max(state.version);
+        changed.notify_all();
+        state.to_json()
+    }
+
+    /// Passes a line from the page to the web player whose turn it is.
+    fn submit(&self, line: &str) -> Result<(), &'static str> {
+        let (lock, changed) = &*self.shared;
+        let mut state = lock.lock().unwrap();
+        let colour: PieceColour = state.awaiting.ok_or("it is not your turn")?;
+        let sender: &Sender<String> = state
+            .inputs
+            .iter()
+            .find(|(input_colour, _)| *input_colour == colour)
+            .map(|(_, sender)| sender)
+            .ok_or("it is not your turn")?;
+        sender
+            .send(line.to_string())
+            .map_err(|_| "the game is over")?;
+        state.awaiting = None;
+        state.message.clear();
+        state.version += 1;
+        changed.notify_all();
+        Ok(())
+    }
+}
+
+impl WebState {
+    /// Plays a move on the page's copy of the board once the game has accepted it.
+    fn apply(&mut self, record: &MoveRecord) {
+        self.board
+            .play_coordinate_move(self.turn, &record.coordinate_move);
+        self.draw_offer = if record.draw_offer {
+            Some(self.turn)
+        } else {
+            None
+        };
+        self.turn = self.turn.opponent();
+        self.moves.push(record.clone());
+    }
+
+    fn to_json(&self) -> String {
+        let (white_ms, black_ms): (u128, u128) = match &self.clocks {
+            Some((white_timer, black_timer)) => (
+                white_timer.remaining_duration().as_millis(),
+                black_timer.remaining_duration().as_millis(),
+            ),
+            None => (0, 0),
+        };
+        let running: &str = if self.clocks.is_some() && self.result.is_none() {
+            colour_name(self.turn)
+        } else {
+            ""
+        };
+        let legal: Vec<String> = match self.awaiting {
+            Some(colour) => self
+                .board
+                .clone()
+                .legal_moves(colour)
+                .into_iter()
+                .map(|coordinate_move| json_string(&coordinate_move.to_notation()))
+                .collect(),
+            None => Vec::new(),
+        };
+        let moves: Vec<String> = self
+            .moves
+            .iter()
+            .map(|record| json_string(&record.san))
+            .collect();
+        let controlled: Vec<String> = self
+            .controlled
+            .iter()
+            .map(|colour| json_string(colour_name(*colour)))
+            .collect();
+
+        // the pieces each side has taken, as FEN letters
+        let taken_by = |colour: PieceColour| -> String {
+            self.board
+                .captured(colour.opponent())
+                .into_iter()
+                .map(|piece_type| match colour {
+                    PieceColour::White => piece_type.letter().to_ascii_lowercase(),
+                    PieceColour::Black => piece_type.letter(),
+                })
+                .collect()
+        };
+
+        format!(
+            "{{\"version\":{},\"fen\":{},\"turn\":{},\"moves\":[{}],\"white_ms\":{},\
+             \"black_ms\":{},\"running\":{},\"controlled\":[{}],\"awaiting\":{},\"legal\":[{}],\
+             \"draw_offer\":{},\"message\":{},\"result\":{},\"white_taken\":{},\
+             \"black_taken\":{},\"balance\":{}}}",
+            self.version,
+            json_string(&self.board.to_fen(self.turn, self.moves.len() / 2 + 1)),
+            json_string(colour_name(self.turn)),
+            moves.join(","),
+            white_ms,
+            black_ms,
+            json_string(running),
+            controlled.join(","),
+            json_string(self.awaiting.map_or("", colour_name)),
+            legal.join(","),
+            json_string(self.draw_offer.map_or("", colour_name)),
+            json_string(&self.message),
+            json_string(self.result.as_deref().unwrap_or_default()),
+            json_string(&taken_by(PieceColour::White)),
+            json_string(&taken_by(PieceColour::Black)),
+            self.board.material(PieceColour::White) - self.board.material(PieceColour::Black)
+        )
+    }
+}
+
+impl Player for WebPlayer {
+    fn request_move(&mut self, _board: &Board, colour: PieceColour) -> PlayerAction {
+        self.set(|state| state.awaiting = Some(colour));
+        let line: String = match self.interrupt.recv(&self.input) {
+            Input::Received(line) => line,
+            Input::Closed => return PlayerAction::Abandon,
+            Input::Interrupted => return PlayerAction::Interrupted,
+        };
+
+        let action: PlayerAction = PlayerAction::parse(&line);
+        if let PlayerAction::Move { notation, .. } = &action {
+            if let Ok(CoordinateMove {
+                promotion: Some(promotion),
+                ..
+            }) = CoordinateMove::from_notation(notation)
+            {
+                self.promotion = promotion;
+            }
+        }
+        action
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        self.promotion
+    }
+
+    fn notify(&mut self, notification: &Notification) {
+        let colour: PieceColour = self.colour;
+        match notification {
+            Notification::MoveAccepted(record) => self.set(|state| state.apply(record)),
+            // with the browser playing both sides the move arrives as MoveAccepted as well
+            Notification::OpponentMoved(record) => self.set(|state| {
+                if !state.controlled.contains(&colour.opponent()) {
+                    state.apply(record);
+                }
+            }),
+            Notification::DrawDeclined => {
+                self.show_message("The opponent declined the draw");
+            }
+            Notification::NoDrawOffer => self.show_message("There is no draw offer to accept"),
+            Notification::MoveRejected(move_result) => {
+                self.show_message(player::rejection_message(*move_result));
+            }
+            Notification::InvalidNotation => self.show_message("Notation is invalid"),
+            Notification::SaveFailed(reason) => {
+                self.show_message(&format!("Could not save the game: {}", reason));
+            }
+            Notification::GameOver {
+                winner,
+                termination,
+            } => {
+                let result: String = match winner {
+                    _ if *termination == Termination::Adjourned => String::from("Game adjourned"),
+                    Some(winner) => format!("{} won by {}", title_case(*winner), termination),
+                    None => format!("Draw by {}", termination),
+                };
+                self.set(|state| {
+                    state.awaiting = None;
+                    state.result = Some(result);
+                });
+            }
+            Notification::DrawOffered => (),
+        }
+    }
+
+    fn interrupt(&self) -> Option<Interrupt> {
+        Some(self.interrupt.clone())
+    }
+}
+
+impl WebPlayer {
+    fn set(&self, change: impl FnOnce(&mut WebState)) {
+        self.server.update(change);
+    }
+
+    fn show_message(&self, message: &str) {
+        self.set(|state| state.message = message.to_string());
+    }
+}
+
+fn respond(mut stream: TcpStream, status: &str, content_type: &str, body: &str) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\n\
+         Connection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    stream.flush()
+}
+
+fn json_string(text: &str) -> String {
+    let mut json: String = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            character if character.is_control() => {
+                json.push_str(&format!("\\u{:04x}", character as u32))
+            }
+            character => json.push(character),
+        }
+    }
+    json.push('"');
+    json
+}
+
+fn colour_name(colour: PieceColour) -> &'static str {
+    match colour {
+        PieceColour::White => "white",
+        PieceColour::Black => "black",
+    }
+}
+
+fn title_case(colour: PieceColour) -> &'static str {
+    match colour {
+        PieceColour::White => "White",
+        PieceColour::Black => "Black",
+    }
+}
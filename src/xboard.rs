@@ -0,0 +1,261 @@
+//! Chess Engine Communication Protocol (XBoard/WinBoard) front end.
+//!
+//! Unlike UCI, where the GUI resends the whole position and both clocks before every `go`,
+//! CECP is stateful: the engine keeps the game itself, `undo` takes back one ply and `remove`
+//! two, and `time`/`otim` set the engine's own clock and its opponent's rather than White's
+//! and Black's. Taking moves back never rewinds the clocks; the GUI resends them instead.
+
+use crate::{
+    board::Board,
+    engine,
+    piece::{PieceColour, PieceType},
+    position::{CoordinateMove, MoveResult},
+    timer::Timer,
+};
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+pub struct XBoardSession {
+    board: Board,
+    turn: PieceColour,
+    history: Vec<(Board, PieceColour)>,
+    engine_colour: PieceColour,
+    force_mode: bool,
+    game_over: bool,
+    white_timer: Timer,
+    black_timer: Timer,
+}
+
+impl XBoardSession {
+    pub fn new(minutes: u64) -> Self {
+        let white_timer: Timer = Timer::new(minutes * 60);
+        let black_timer: Timer = Timer::new(minutes * 60);
+        white_timer.pause();
+        black_timer.pause();
+        white_timer.countdown_start();
+        black_timer.countdown_start();
+
+        XBoardSession {
+            board: Board::new(),
+            turn: PieceColour::White,
+            history: Vec::new(),
+            engine_colour: PieceColour::Black,
+            force_mode: false,
+            game_over: false,
+            white_timer,
+            black_timer,
+        }
+    }
+
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        for line in input.lines() {
+            let line: String = line?;
+            if !self.handle_command(line.trim(), output)? {
+                break;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Returns `false` once the GUI asks the engine to quit.
+    fn handle_command<W: Write>(&mut self, line: &str, output: &mut W) -> io::Result<bool> {
+        let (command, argument): (&str, &str) = match line.split_once(' ') {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "" | "xboard" | "accepted" | "rejected" | "random" | "hard" | "easy" | "post"
+            | "nopost" | "computer" | "name" | "level" | "st" | "sd" | "?" => (),
+            "protover" => writeln!(
+                output,
+                "feature myname=\"chess\" usermove=1 setboard=1 ping=1 sigint=0 sigterm=0 done=1"
+            )?,
+            "ping" => writeln!(output, "pong {}", argument)?,
+            "quit" => return Ok(false),
+            "new" => {
+                self.board = Board::new();
+                self.turn = PieceColour::White;
+                self.history.clear();
+                self.engine_colour = PieceColour::Black;
+                self.force_mode = false;
+                self.game_over = false;
+                self.update_clocks();
+            }
+            "force" => {
+                self.force_mode = true;
+                self.update_clocks();
+            }
+            "go" => {
+                self.force_mode = false;
+                self.engine_colour = self.turn;
+                self.engine_move(output)?;
+            }
+            "playother" => {
+                self.force_mode = false;
+                self.engine_colour = self.turn.opponent();
+                self.update_clocks();
+            }
+            "time" | "otim" => {
+                let centiseconds: u64 = match argument.parse::<u64>() {
+                    Ok(centiseconds) => centiseconds,
+                    Err(_) => {
+                        writeln!(output, "Error (bad argument): {}", line)?;
+                        return Ok(true);
+                    }
+                };
+                let colour: PieceColour = if command == "time" {
+                    self.engine_colour
+                } else {
+                    self.engine_colour.opponent()
+                };
+                let remaining: Duration = Duration::from_millis(centiseconds * 10);
+                let timer: &Timer = self.timer(colour);
+                let was_finished: bool = timer.is_finished();
+                timer.set_remaining(remaining);
+                // a clock that ran out has no countdown left to pick up the new time
+                if was_finished && !remaining.is_zero() {
+                    timer.countdown_start();
+                    self.update_clocks();
+                }
+            }
+            "undo" => self.take_back(1),
+            "remove" => self.take_back(2),
+            "result" => {
+                self.game_over = true;
+                self.force_mode = true;
+                self.update_clocks();
+            }
+            "setboard" => match Board::from_fen(argument) {
+                Ok((board, turn)) => {
+                    self.board = board;
+                    self.turn = turn;
+                    self.history.clear();
+                    self.game_over = false;
+                    self.update_clocks();
+                }
+                Err(_) => writeln!(output, "tellusererror Illegal position")?,
+            },
+            "usermove" => self.user_move(argument, output)?,
+            _ => {
+                if CoordinateMove::from_notation(line).is_ok() {
+                    self.user_move(line, output)?;
+                } else {
+                    writeln!(output, "Error (unknown command): {}", command)?;
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn user_move<W: Write>(&mut self, notation: &str, output: &mut W) -> io::Result<()> {
+        let coordinate_move: CoordinateMove = match CoordinateMove::from_notation(notation) {
+            Ok(coordinate_move) => coordinate_move,
+            Err(_) => return writeln!(output, "Illegal move: {}", notation),
+        };
+
+        if self.game_over || !self.apply(&coordinate_move) {
+            return writeln!(output, "Illegal move: {}", notation);
+        }
+
+        self.report_game_end(output)?;
+        if !self.force_mode && self.turn == self.engine_colour {
+            self.engine_move(output)?;
+        }
+        Ok(())
+    }
+
+    fn engine_move<W: Write>(&mut self, output: &mut W) -> io::Result<()> {
+        if self.game_over {
+            return Ok(());
+        }
+        self.update_clocks();
+
+        let coordinate_move: CoordinateMove = match engine::choose_move(&self.board, self.turn) {
+            Some(coordinate_move) => coordinate_move,
+            None => return self.report_game_end(output),
+        };
+
+        self.apply(&coordinate_move);
+        writeln!(output, "move {}", coordinate_move.to_notation())?;
+        self.report_game_end(output)
+    }
+
+    /// Plays the move for the side to move, returning `false` if it is not legal.
+    fn apply(&mut self, coordinate_move: &CoordinateMove) -> bool {
+        let previous: Board = self.board.clone();
+        let mut coordinate_move: CoordinateMove = *coordinate_move;
+        if coordinate_move.promotion.is_none() {
+            coordinate_move.promotion = Some(PieceType::Queen);
+        }
+
+        match self.board.play_coordinate_move(self.turn, &coordinate_move) {
+            MoveResult::Success | MoveResult::PromotionAvailable(_) => (),
+            MoveResult::Draw => self.game_over = true,
+            _ => {
+                self.board = previous;
+                return false;
+            }
+        }
+
+        self.history.push((previous, self.turn));
+        self.turn = self.turn.opponent();
+        self.update_clocks();
+        true
+    }
+
+    fn report_game_end<W: Write>(&mut self, output: &mut W) -> io::Result<()> {
+        let result: &str = if self.board.is_mate(self.turn) {
+            match self.turn {
+                PieceColour::White => "0-1 {Black mates}",
+                PieceColour::Black => "1-0 {White mates}",
+            }
+        } else if self.board.is_stalemate(self.turn) {
+            "1/2-1/2 {Stalemate}"
+        } else if self.game_over {
+            "1/2-1/2 {50 move rule}"
+        } else {
+            return Ok(());
+        };
+
+        self.game_over = true;
+        self.update_clocks();
+        writeln!(output, "{}", result)
+    }
+
+    fn take_back(&mut self, plies: usize) {
+        for _ in 0..plies {
+            match self.history.pop() {
+                Some((board, turn)) => {
+                    self.board = board;
+                    self.turn = turn;
+                    self.game_over = false;
+                }
+                None => break,
+            }
+        }
+        self.update_clocks();
+    }
+
+    /// The time `colour` has left, as last set by `time`/`otim` and run down since.
+    pub fn remaining(&self, colour: PieceColour) -> Duration {
+        self.timer(colour).remaining_duration()
+    }
+
+    fn timer(&self, colour: PieceColour) -> &Timer {
+        match colour {
+            PieceColour::White => &self.white_timer,
+            PieceColour::Black => &self.black_timer,
+        }
+    }
+
+    /// Only the clock of the side to move runs, and none do while the engine is in force mode.
+    fn update_clocks(&self) {
+        self.white_timer.pause();
+        self.black_timer.pause();
+        if !self.force_mode && !self.game_over {
+            self.timer(self.turn).resume();
+        }
+    }
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use chess::variant::{Atomic, Variant};
+use chess::{Board, MoveResult, PieceColour, PieceType};
+use common::square;
+
+fn atomic(fen: &str) -> (Board, PieceColour) {
+    Atomic.read_fen(fen).unwrap()
+}
+
+#[test]
+fn a_capture_explodes_every_piece_but_pawns_around_it() {
+    let (mut board, turn) = atomic("4k3/8/8/2bnp3/3r4/4N3/8/3QK3 w - - 0 1");
+    // trying out every move puts the exploded pieces back
+    let before: String = board.to_fen(turn, 1);
+    assert!(!board.legal_moves(turn).is_empty());
+    assert_eq!(board.to_fen(turn, 1), before);
+
+    assert_eq!(
+        board.play_notation(PieceColour::White, "d1d4").unwrap(),
+        MoveResult::Success
+    );
+    for gone in ["d4", "c5", "d5", "e3"] {
+        assert!(board.piece_at(square(gone)).is_none(), "{}", gone);
+    }
+    assert_eq!(
+        board.piece_at(square("e5")).map(|piece| piece.piece_type),
+        Some(PieceType::Pawn)
+    );
+}
+
+#[test]
+fn kings_cannot_capture_and_can_stand_side_by_side() {
+    // the rook on e4 would give check, but taking the king would blow up the black king too
+    let (mut board, turn) = atomic("8/8/8/8/4r3/3kK3/8/8 w - - 0 1");
+    assert_eq!(board.checked_king(turn), None);
+    assert!(!board
+        .legal_moves(turn)
+        .iter()
+        .any(|coordinate_move| coordinate_move.to == square("e4")));
+}
+
+#[test]
+fn a_capture_may_not_blow_up_your_own_king() {
+    let (mut board, _) = atomic("8/8/8/8/8/8/3pK3/k2R4 w - - 0 1");
+    assert_eq!(
+        board.play_notation(PieceColour::White, "d1d2").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    assert!(board.has_king(PieceColour::White));
+}
+
+#[test]
+fn blowing_up_the_enemy_king_is_allowed_even_in_check() {
+    let (mut board, turn) = atomic("3rk3/3p4/8/8/8/8/8/3RK2r w - - 0 1");
+    assert!(board.checked_king(turn).is_some());
+    assert_eq!(
+        board.play_notation(PieceColour::White, "d1d7").unwrap(),
+        MoveResult::Success
+    );
+    assert!(!board.has_king(PieceColour::Black));
+}
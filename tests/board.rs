@@ -0,0 +1,186 @@
+mod common;
+
+use chess::{Board, CoordinateMove, MoveResult, PieceColour, PieceType, Position};
+use common::square;
+
+/// Plays moves in any notation `Board::play_notation` accepts, alternating from White.
+fn play(board: &mut Board, moves: &[&str]) -> MoveResult {
+    let mut turn: PieceColour = PieceColour::White;
+    let mut result: MoveResult = MoveResult::Success;
+    for notation in moves {
+        result = board.play_notation(turn, notation).unwrap();
+        assert!(
+            matches!(result, MoveResult::Success | MoveResult::Draw),
+            "{} was not played: {:?}",
+            notation,
+            result
+        );
+        turn = turn.opponent();
+    }
+    result
+}
+
+#[test]
+fn the_starting_position_has_twenty_moves() {
+    let mut board: Board = Board::new();
+    assert_eq!(board.legal_moves(PieceColour::White).len(), 20);
+    assert_eq!(board.legal_moves(PieceColour::Black).len(), 20);
+}
+
+#[test]
+fn moves_are_played_in_algebraic_and_coordinate_notation() {
+    let mut board: Board = Board::new();
+    play(&mut board, &["e4", "e7e5", "Nf3"]);
+    let knight = board.piece_at(square("f3")).unwrap();
+    assert_eq!(knight.piece_type, PieceType::Knight);
+    assert_eq!(knight.colour, PieceColour::White);
+    assert!(board.piece_at(square("g1")).is_none());
+    assert_eq!(
+        board.last_move(),
+        Some(CoordinateMove::new(square("g1"), square("f3"), None))
+    );
+}
+
+#[test]
+fn illegal_moves_leave_the_board_alone() {
+    let mut board: Board = Board::new();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "e2e5").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    assert_eq!(
+        board.play_notation(PieceColour::White, "e7e5").unwrap(),
+        MoveResult::MissingPiece
+    );
+    assert!(board.play_notation(PieceColour::White, "z9").is_err());
+    assert_eq!(
+        board.to_fen(PieceColour::White, 1),
+        Board::new().to_fen(PieceColour::White, 1)
+    );
+}
+
+#[test]
+fn a_pinned_piece_cannot_move() {
+    let (mut board, turn) = Board::from_fen("4k3/8/8/8/4r3/8/4N3/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(turn, "Nc3").unwrap(),
+        MoveResult::PiecePinned
+    );
+}
+
+#[test]
+fn castling_moves_king_and_rook() {
+    let mut board: Board = Board::new();
+    play(&mut board, &["e4", "e5", "Nf3", "Nc6", "Bc4", "Bc5", "O-O"]);
+    assert_eq!(
+        board.piece_at(square("g1")).unwrap().piece_type,
+        PieceType::King
+    );
+    assert_eq!(
+        board.piece_at(square("f1")).unwrap().piece_type,
+        PieceType::Rook
+    );
+}
+
+#[test]
+fn pawns_capture_en_passant() {
+    let mut board: Board = Board::new();
+    play(&mut board, &["e4", "a6", "e5", "d5", "e5d6"]);
+    assert!(board.piece_at(square("d5")).is_none());
+    assert_eq!(
+        board.piece_at(square("d6")).unwrap().piece_type,
+        PieceType::Pawn
+    );
+}
+
+#[test]
+fn pawns_promote_on_the_last_rank() {
+    let (mut board, turn) = Board::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(turn, "e7e8").unwrap(),
+        MoveResult::PromotionAvailable(square("e8"))
+    );
+    board.promote(square("e8"), turn, PieceType::Knight);
+    assert_eq!(
+        board.piece_at(square("e8")).unwrap().piece_type,
+        PieceType::Knight
+    );
+
+    let (mut board, turn) = Board::from_fen("8/4P3/8/8/8/8/k7/4K3 w - - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(turn, "e7e8q").unwrap(),
+        MoveResult::Success
+    );
+    assert_eq!(
+        board.piece_at(square("e8")).unwrap().piece_type,
+        PieceType::Queen
+    );
+}
+
+#[test]
+fn checkmate_and_stalemate_are_detected() {
+    let mut board: Board = Board::new();
+    play(&mut board, &["f3", "e5", "g4", "Qh4"]);
+    assert!(board.is_mate(PieceColour::White));
+    assert_eq!(board.checked_king(PieceColour::White), Some(square("e1")));
+
+    let (mut board, turn) = Board::from_fen("k7/8/1Q6/8/8/8/8/7K b - - 0 1").unwrap();
+    assert!(board.is_stalemate(turn));
+    assert!(!board.is_mate(turn));
+}
+
+#[test]
+fn captured_pieces_and_material_follow_the_game() {
+    let mut board: Board = Board::new();
+    play(&mut board, &["e4", "d5", "ed5", "Qd5"]);
+    assert_eq!(board.captured(PieceColour::White), vec![PieceType::Pawn]);
+    assert_eq!(board.captured(PieceColour::Black), vec![PieceType::Pawn]);
+    assert_eq!(
+        board.material(PieceColour::White),
+        board.material(PieceColour::Black)
+    );
+}
+
+#[test]
+fn destinations_lists_where_a_piece_can_go() {
+    let board: Board = Board::new();
+    let mut destinations: Vec<Position> = board.destinations(PieceColour::White, square("g1"));
+    destinations.sort_by_key(|position| position.column);
+    assert_eq!(destinations, vec![square("f3"), square("h3")]);
+}
+
+#[test]
+fn destinations_keep_pins_and_include_castling_and_en_passant() {
+    let (board, turn) = Board::from_fen("4k3/8/8/8/4r3/8/4R3/4K3 w - - 0 1").unwrap();
+    let mut destinations: Vec<Position> = board.destinations(turn, square("e2"));
+    destinations.sort_by_key(|position| position.row);
+    assert_eq!(destinations, vec![square("e3"), square("e4")]);
+    let (board, turn) = Board::from_fen("4k3/8/8/8/4r3/8/4N3/4K3 w - - 0 1").unwrap();
+    assert!(board.destinations(turn, square("e2")).is_empty());
+
+    let (board, turn) = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    let destinations: Vec<Position> = board.destinations(turn, square("e1"));
+    assert!(destinations.contains(&square("g1")));
+    assert!(destinations.contains(&square("c1")));
+
+    let mut board: Board = Board::new();
+    play(&mut board, &["e4", "a6", "e5", "d5"]);
+    let mut destinations: Vec<Position> = board.destinations(PieceColour::White, square("e5"));
+    destinations.sort_by_key(|position| position.column);
+    assert_eq!(destinations, vec![square("d6"), square("e6")]);
+}
+
+#[test]
+fn insufficient_material_is_detected() {
+    for (fen, insufficient) in [
+        ("4k3/8/8/8/8/8/8/4K3 w - - 0 1", true),
+        ("4k3/8/8/8/8/8/8/2N1K3 w - - 0 1", true),
+        ("2b1k3/8/8/8/8/8/8/2B1K3 w - - 0 1", false),
+        ("3bk3/8/8/8/8/8/8/2B1K3 w - - 0 1", true),
+        ("4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1", false),
+        ("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1", false),
+    ] {
+        let (board, _) = Board::from_fen(fen).unwrap();
+        assert_eq!(board.is_insufficient_material(), insufficient, "{}", fen);
+    }
+}
@@ -0,0 +1,195 @@
+mod common;
+
+use chess::board::STANDARD_BACK_RANK;
+use chess::chess960;
+use chess::pgn::{self, MoveRecord};
+use chess::variant::Standard;
+use chess::{Board, CoordinateMove, MoveResult, PieceColour, PieceType};
+use common::square;
+
+fn piece_type_at(board: &Board, notation: &str) -> Option<PieceType> {
+    board
+        .piece_at(square(notation))
+        .map(|piece| piece.piece_type)
+}
+
+#[test]
+fn positions_are_numbered_as_in_scharnagls_scheme() {
+    assert_eq!(
+        chess960::back_rank(chess960::STANDARD_INDEX),
+        Some(STANDARD_BACK_RANK)
+    );
+    assert_eq!(chess960::back_rank(960), None);
+    assert_eq!(
+        chess960::start_position(0)
+            .unwrap()
+            .to_fen(PieceColour::White, 1),
+        "bbqnnrkr/pppppppp/8/8/8/8/PPPPPPPP/BBQNNRKR w KQkq - 0 1"
+    );
+}
+
+#[test]
+fn every_position_is_different_and_follows_the_rules() {
+    let mut seen: Vec<[PieceType; 8]> = Vec::new();
+    for index in 0..chess960::POSITION_COUNT {
+        let rank: [PieceType; 8] = chess960::back_rank(index).unwrap();
+        assert!(!seen.contains(&rank), "position {} repeats another", index);
+        seen.push(rank);
+
+        let columns = |piece_type: PieceType| -> Vec<usize> {
+            (0..8).filter(|&i| rank[i] == piece_type).collect()
+        };
+        let bishops: Vec<usize> = columns(PieceType::Bishop);
+        let rooks: Vec<usize> = columns(PieceType::Rook);
+        let king: Vec<usize> = columns(PieceType::King);
+        assert_eq!(bishops.len(), 2);
+        assert_ne!(bishops[0] % 2, bishops[1] % 2, "position {}", index);
+        assert_eq!(king.len(), 1);
+        assert!(
+            rooks[0] < king[0] && king[0] < rooks[1],
+            "position {}",
+            index
+        );
+    }
+}
+
+#[test]
+fn a_king_next_to_its_rook_castles_by_taking_it() {
+    let (mut board, _) = Board::from_fen("4k3/8/8/8/8/8/8/R5KR w KQ - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O").unwrap(),
+        MoveResult::Success
+    );
+    assert_eq!(piece_type_at(&board, "g1"), Some(PieceType::King));
+    assert_eq!(piece_type_at(&board, "f1"), Some(PieceType::Rook));
+    assert_eq!(piece_type_at(&board, "h1"), None);
+    // the king did not move, so the move is written as the king taking its rook
+    assert_eq!(
+        board.last_move(),
+        Some(CoordinateMove::new(square("g1"), square("h1"), None))
+    );
+}
+
+#[test]
+fn castling_lands_on_the_standard_squares() {
+    let (mut board, _) = Board::from_fen("4k3/8/8/8/8/8/8/RK4R1 w KQ - 0 1").unwrap();
+    let mut queen_side: Board = board.clone();
+    assert_eq!(
+        queen_side
+            .play_notation(PieceColour::White, "O-O-O")
+            .unwrap(),
+        MoveResult::Success
+    );
+    assert_eq!(piece_type_at(&queen_side, "c1"), Some(PieceType::King));
+    assert_eq!(piece_type_at(&queen_side, "d1"), Some(PieceType::Rook));
+    assert_eq!(piece_type_at(&queen_side, "a1"), None);
+
+    // the king taking its own rook is castling too
+    let king_side: CoordinateMove = CoordinateMove::from_notation("b1g1").unwrap();
+    assert_eq!(board.san(PieceColour::White, &king_side), "O-O");
+    assert_eq!(
+        board.play_coordinate_move(PieceColour::White, &king_side),
+        MoveResult::Success
+    );
+    assert_eq!(piece_type_at(&board, "g1"), Some(PieceType::King));
+    assert_eq!(piece_type_at(&board, "f1"), Some(PieceType::Rook));
+}
+
+#[test]
+fn castling_needs_the_king_and_rook_paths_clear_and_safe() {
+    // the knight on b1 stands on the rook's way to d1 but not the king's
+    let (mut board, _) = Board::from_fen("4k3/8/8/8/8/8/8/RN1K4 w Q - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O-O").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    // a pawn on d2 covers c1, where the king would end up
+    let (mut board, _) = Board::from_fen("4k3/8/8/8/8/8/3p4/R2K4 w Q - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O-O").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    // the king passes d1, where its rook stands, and the rook on d8 covers it
+    let (mut board, _) = Board::from_fen("3rk3/8/8/8/8/8/8/3R2K1 w Q - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O-O").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+}
+
+#[test]
+fn standard_castling_is_blocked_by_any_piece_in_between() {
+    let (mut board, _) = Board::from_fen("4k3/8/8/8/8/8/8/RN2KB1R w KQ - 0 1").unwrap();
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    assert_eq!(
+        board.play_notation(PieceColour::White, "O-O-O").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+}
+
+#[test]
+fn castling_rights_are_written_in_x_fen() {
+    // the outermost rooks are K and Q, an inner rook is named by its file
+    let (board, turn) = Board::from_fen("rr2k2r/8/8/8/8/8/8/1R2K1RR w GBhb - 0 1").unwrap();
+    assert_eq!(
+        board.to_fen(turn, 1),
+        "rr2k2r/8/8/8/8/8/8/1R2K1RR w GQkb - 0 1"
+    );
+
+    let (board, turn) = Board::from_fen("rr2k2r/8/8/8/8/8/8/1R2K1RR w HBhb - 0 1").unwrap();
+    assert_eq!(
+        board.to_fen(turn, 1),
+        "rr2k2r/8/8/8/8/8/8/1R2K1RR w KQkb - 0 1"
+    );
+}
+
+#[test]
+fn chess960_games_are_tagged_in_pgn() {
+    let board: Board = chess960::start_position(0).unwrap();
+    let start: String = board.to_fen(PieceColour::White, 1);
+    assert!(board.is_chess960());
+    let records: Vec<MoveRecord> = Vec::new();
+    let text: String = pgn::to_pgn_from(&Standard, &start, &records, "*");
+    assert!(text.contains("[Variant \"Chess960\"]"));
+    assert!(text.contains(&format!("[FEN \"{}\"]", start)));
+
+    let text: String = pgn::to_pgn_from(
+        &Standard,
+        &Board::new().to_fen(PieceColour::White, 1),
+        &records,
+        "*",
+    );
+    assert!(!text.contains("Variant") && !text.contains("FEN"));
+}
+
+#[test]
+fn each_side_can_have_its_own_back_rank() {
+    let white: [PieceType; 8] = chess960::parse_back_rank("NRKBBQRN").unwrap();
+    let board: Board = Board::with_back_ranks(white, STANDARD_BACK_RANK);
+    assert_eq!(
+        board.to_fen(PieceColour::White, 1),
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/NRKBBQRN w KQkq - 0 1"
+    );
+
+    let (_, _, board) = chess960::double_start_position();
+    let fen: String = board.to_fen(PieceColour::White, 1);
+    let ranks: Vec<&str> = fen.split(['/', ' ']).collect();
+    for rank in [ranks[0], ranks[7]] {
+        assert!(chess960::parse_back_rank(rank).is_ok(), "{}", fen);
+    }
+}
+
+#[test]
+fn back_ranks_are_checked() {
+    assert!(chess960::parse_back_rank("rnbqkbnr").is_ok());
+    for rank in [
+        "RNBQKBN", "RNBQXBNR", "RNBQQBNR", "RNBKKBNR", "RNBQKPNR", "BRBQKRNN",
+        // only the standard pieces, which captures are counted against
+        "QQBQKBNR", "RNNQKNNR", "RRBQKBRR",
+    ] {
+        assert!(chess960::parse_back_rank(rank).is_err(), "{}", rank);
+    }
+}
@@ -0,0 +1,71 @@
+//! Fixtures shared by the integration tests. Each test crate uses only some of them.
+#![allow(dead_code)]
+
+use chess::player::{Input, Interrupt, Notification, PlayerAction, ScriptedPlayer};
+use chess::render::{Frame, Line, Renderer, SharedRenderer};
+use chess::{Board, PieceColour, PieceType, Player, Position};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Draws nothing, so games can run without a terminal.
+pub struct Silent;
+
+impl Renderer for Silent {
+    fn frame(&mut self, _frame: &Frame) {}
+    fn tick(&mut self, _colour: PieceColour, _remaining: Duration) {}
+    fn line(&mut self, _line: Line, _text: &str) {}
+}
+
+pub fn silent() -> SharedRenderer {
+    Arc::new(Mutex::new(Silent))
+}
+
+/// A player making `moves` in order, in any notation a person could type.
+pub fn scripted(moves: &[&str]) -> Box<ScriptedPlayer> {
+    Box::new(ScriptedPlayer::new(
+        moves.iter().map(|notation| notation.to_string()).collect(),
+    ))
+}
+
+pub fn square(notation: &str) -> Position {
+    Position::from_notation(notation).unwrap()
+}
+
+/// Waits for a move that never comes, like someone who has left the keyboard.
+pub struct Away {
+    interrupt: Interrupt,
+    _sender: Sender<String>,
+    input: Receiver<String>,
+}
+
+impl Away {
+    pub fn new() -> Self {
+        let (sender, input): (Sender<String>, Receiver<String>) = mpsc::channel();
+        Away {
+            interrupt: Interrupt::default(),
+            _sender: sender,
+            input,
+        }
+    }
+}
+
+impl Player for Away {
+    fn request_move(&mut self, _board: &Board, _colour: PieceColour) -> PlayerAction {
+        match self.interrupt.recv(&self.input) {
+            Input::Received(line) => PlayerAction::parse(&line),
+            Input::Closed => PlayerAction::Abandon,
+            Input::Interrupted => PlayerAction::Interrupted,
+        }
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        PieceType::Queen
+    }
+
+    fn notify(&mut self, _notification: &Notification) {}
+
+    fn interrupt(&self) -> Option<Interrupt> {
+        Some(self.interrupt.clone())
+    }
+}
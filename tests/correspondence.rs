@@ -0,0 +1,171 @@
+mod common;
+
+use chess::correspondence;
+use chess::save::SavedGame;
+use chess::PieceColour;
+use common::silent;
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+use std::process;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A save file of its own for each test, removed when the test ends.
+struct SaveFile(PathBuf);
+
+impl SaveFile {
+    fn new(name: &str) -> Self {
+        let path: PathBuf = env::temp_dir().join(format!(
+            "chess-correspondence-{}-{}.txt",
+            name,
+            process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        SaveFile(path)
+    }
+
+    /// Runs one turn with `typed` as everything the player types.
+    fn turn(&self, typed: &str) -> SavedGame {
+        correspondence::play_turn_from(&self.0, 3, Cursor::new(typed.to_string()), silent())
+            .unwrap();
+        SavedGame::read(&self.0).unwrap()
+    }
+
+    fn write(&self, text: &str) {
+        fs::write(&self.0, text).unwrap();
+    }
+}
+
+impl Drop for SaveFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// A minute ago, well within any deadline.
+fn recent() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        - 60
+}
+
+#[test]
+fn each_run_plays_one_move_for_the_side_to_move() {
+    let file: SaveFile = SaveFile::new("play");
+    // a bad move is refused and the player asked again
+    let saved: SavedGame = file.turn("e5\ne4\nd4\n");
+    assert_eq!(saved.days_per_move, Some(3));
+    assert_eq!(saved.moves.len(), 1);
+    assert_eq!(saved.timestamps.len(), 1);
+    assert_eq!(
+        saved.position,
+        "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1"
+    );
+
+    let saved: SavedGame = file.turn("f5\n");
+    assert_eq!(saved.moves.len(), 2);
+    assert_eq!(saved.result, None);
+
+    // nothing typed leaves the game as it was
+    let saved: SavedGame = file.turn("");
+    assert_eq!(saved.moves.len(), 2);
+    let saved: SavedGame = file.turn("Qh5\n");
+    assert_eq!(saved.moves.len(), 3);
+}
+
+#[test]
+fn a_draw_offer_is_answered_on_the_next_run() {
+    let file: SaveFile = SaveFile::new("draw");
+    file.turn("e4\n");
+    let saved: SavedGame = file.turn("accept\ne5 draw\n");
+    assert_eq!(saved.draw_offer, Some(PieceColour::Black));
+    assert_eq!(saved.result, None);
+    let saved: SavedGame = file.turn("accept\n");
+    assert_eq!(saved.result.as_deref(), Some("1/2-1/2 agreement"));
+    assert_eq!(saved.moves.len(), 2);
+
+    // a finished game takes no more moves
+    let saved: SavedGame = file.turn("Nf3\n");
+    assert_eq!(saved.moves.len(), 2);
+
+    let file: SaveFile = SaveFile::new("declined");
+    file.turn("e4 draw\n");
+    let saved: SavedGame = file.turn("e5\n");
+    assert_eq!(saved.draw_offer, None);
+    let saved: SavedGame = file.turn("accept\nresign\n");
+    assert_eq!(saved.result.as_deref(), Some("0-1 resignation"));
+}
+
+#[test]
+fn missing_the_deadline_forfeits_the_game() {
+    let file: SaveFile = SaveFile::new("deadline");
+    file.write(
+        "chess-save 1
+time-control 0
+start rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1
+moves e2e4
+days-per-move 3
+started 1000000
+timestamps 1000000
+",
+    );
+    let saved: SavedGame = file.turn("e5\n");
+    assert_eq!(saved.moves.len(), 1);
+    assert_eq!(saved.result.as_deref(), Some("1-0 time forfeit"));
+}
+
+#[test]
+fn variant_games_end_by_the_variants_rules() {
+    let file: SaveFile = SaveFile::new("three-check");
+    file.write(&format!(
+        "chess-save 1
+time-control 0
+variant Three-check
+start 4k3/8/8/8/8/8/8/Q3K3 w - - 1+3 0 1
+days-per-move 3
+started {}
+",
+        recent()
+    ));
+    let saved: SavedGame = file.turn("Qa4\n");
+    assert_eq!(saved.result.as_deref(), Some("1-0 three checks"));
+    // the position keeps the variant's own fields
+    assert_eq!(saved.position, "4k3/8/8/8/Q7/8/8/4K3 b - - 0+3 1 1");
+
+    let file: SaveFile = SaveFile::new("atomic");
+    file.write(&format!(
+        "chess-save 1
+time-control 0
+variant Atomic
+start 4k3/3p4/8/8/8/8/8/3QK3 w - - 0 1
+days-per-move 3
+started {}
+",
+        recent()
+    ));
+    let saved: SavedGame = file.turn("d1d7\n");
+    assert_eq!(saved.result.as_deref(), Some("1-0 exploding the king"));
+}
+
+#[test]
+fn a_pawn_promotes_to_the_piece_typed_next() {
+    let file: SaveFile = SaveFile::new("promotion");
+    file.write(&format!(
+        "chess-save 1
+time-control 0
+start 8/4P3/8/8/8/8/k7/4K3 w - - 0 1
+days-per-move 3
+started {}
+",
+        recent()
+    ));
+    let saved: SavedGame = file.turn("e8\nx\nn\n");
+    assert_eq!(saved.position, "4N3/8/8/8/8/8/k7/4K3 b - - 0 1");
+    assert_eq!(
+        saved.result.as_deref(),
+        Some("1/2-1/2 insufficient material")
+    );
+}
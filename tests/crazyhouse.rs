@@ -0,0 +1,108 @@
+mod common;
+
+use chess::pgn;
+use chess::variant::{Crazyhouse, Variant};
+use chess::{Board, CoordinateMove, MoveResult, PieceColour, PieceType};
+use common::square;
+
+fn crazyhouse(fen: &str) -> (Board, PieceColour) {
+    Crazyhouse.read_fen(fen).unwrap()
+}
+
+#[test]
+fn captured_pieces_go_to_the_capturers_pocket() {
+    let (mut board, _) = crazyhouse("4k3/8/8/3n4/8/8/8/3QK3 w - - 0 1");
+    assert_eq!(
+        board.play_notation(PieceColour::White, "d1d5").unwrap(),
+        MoveResult::Success
+    );
+    assert_eq!(board.pocket(PieceColour::White), &[PieceType::Knight]);
+    assert!(board.pocket(PieceColour::Black).is_empty());
+    assert_eq!(
+        board.to_fen(PieceColour::Black, 1),
+        "4k3/8/8/3Q4/8/8/8/4K3[N] b - - 0 1"
+    );
+}
+
+#[test]
+fn pieces_are_dropped_from_the_pocket() {
+    let (mut board, _) = crazyhouse("4k3/8/8/8/8/8/8/4K3[Nq] w - - 0 1");
+    let drop: CoordinateMove = CoordinateMove::from_notation("N@f3").unwrap();
+    assert_eq!(
+        drop,
+        CoordinateMove::new_drop(PieceType::Knight, square("f3"))
+    );
+    assert_eq!(board.san(PieceColour::White, &drop), "N@f3");
+    assert_eq!(
+        board.play_coordinate_move(PieceColour::White, &drop),
+        MoveResult::Success
+    );
+    assert_eq!(
+        board.piece_at(square("f3")).map(|piece| piece.piece_type),
+        Some(PieceType::Knight)
+    );
+    assert!(board.pocket(PieceColour::White).is_empty());
+
+    // nothing left to drop, an occupied square, and a pawn on the last rank
+    assert_eq!(
+        board.play_notation(PieceColour::White, "N@g3").unwrap(),
+        MoveResult::MissingPiece
+    );
+    assert_eq!(
+        board.play_notation(PieceColour::Black, "Q@f3").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    let (mut board, _) = crazyhouse("4k3/8/8/8/8/8/8/4K3[P] w - - 0 1");
+    assert_eq!(
+        board.play_notation(PieceColour::White, "@e8").unwrap(),
+        MoveResult::ImpossibleMove
+    );
+    assert_eq!(
+        board.play_notation(PieceColour::White, "P@e7").unwrap(),
+        MoveResult::Success
+    );
+}
+
+#[test]
+fn a_promoted_piece_goes_back_as_a_pawn() {
+    let (mut board, turn) = crazyhouse("4k3/8/8/8/8/8/7K/Q~6r b - - 0 1");
+    assert_eq!(board.to_fen(turn, 1), "4k3/8/8/8/8/8/7K/Q~6r[] b - - 0 1");
+    assert_eq!(
+        board.play_notation(PieceColour::Black, "h1a1").unwrap(),
+        MoveResult::Success
+    );
+    assert_eq!(board.pocket(PieceColour::Black), &[PieceType::Pawn]);
+}
+
+#[test]
+fn a_drop_can_block_mate() {
+    // the rook on a1 gives check along the back rank, and a piece dropped on b1 to g1 blocks it
+    let (mut board, _) = crazyhouse("k7/8/8/8/8/5ppp/8/r6K w - - 0 1");
+    assert!(board.is_mate(PieceColour::White));
+    let (mut board, _) = crazyhouse("k7/8/8/8/8/5ppp/8/r6K[B] w - - 0 1");
+    assert!(!board.is_mate(PieceColour::White));
+    assert_eq!(
+        board.play_notation(PieceColour::White, "B@e1").unwrap(),
+        MoveResult::Success
+    );
+}
+
+#[test]
+fn crazyhouse_games_read_back_from_pgn() {
+    let text: &str = "[Variant \"Crazyhouse\"]\n\n1. e4 d5 2. exd5 Qxd5 3. @e4 *";
+    let game = pgn::read_pgn(text).unwrap();
+    let mut board: Board = game.start.clone();
+    for (coordinate_move, turn) in game.moves.iter().zip([
+        PieceColour::White,
+        PieceColour::Black,
+        PieceColour::White,
+        PieceColour::Black,
+        PieceColour::White,
+    ]) {
+        board.play_coordinate_move(turn, coordinate_move);
+    }
+    assert_eq!(
+        board.to_fen(PieceColour::Black, 3),
+        "rnb1kbnr/ppp1pppp/8/3q4/4P3/8/PPPP1PPP/RNBQKBNR[p] b KQkq - 0 3"
+    );
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use chess::game::GameEvent;
+use chess::save::SavedGame;
+use chess::{GameManager, PieceColour, PieceType, Termination};
+use common::{scripted, silent, square};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[test]
+fn listeners_see_moves_checks_draw_offers_and_the_end() {
+    let mut game: GameManager =
+        GameManager::new(5, scripted(&["f3", "g4"]), scripted(&["e5 draw", "Qh4"]));
+    game.set_renderer(silent());
+    let events: Arc<Mutex<Vec<GameEvent>>> = Arc::new(Mutex::new(Vec::new()));
+    let seen: Arc<Mutex<Vec<GameEvent>>> = Arc::clone(&events);
+    game.on_event(Box::new(move |event| {
+        seen.lock().unwrap().push(event.clone())
+    }));
+    game.start_game();
+
+    let events = events.lock().unwrap();
+    let moves: Vec<&str> = events
+        .iter()
+        .filter_map(|event| match event {
+            GameEvent::Moved { record, .. } => Some(record.san.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(moves, vec!["f3", "e5", "g4", "Qh4#"]);
+    assert!(events
+        .iter()
+        .any(|event| matches!(event, GameEvent::DrawOffered(PieceColour::Black))));
+    assert!(matches!(
+        events[events.len() - 2],
+        GameEvent::Check {
+            colour: PieceColour::White,
+            ..
+        }
+    ));
+    match events.last().unwrap() {
+        GameEvent::GameOver {
+            winner,
+            termination,
+        } => {
+            assert_eq!(*winner, Some(PieceColour::Black));
+            assert_eq!(*termination, Termination::Checkmate);
+        }
+        event => panic!("the game ended with {:?}", event),
+    }
+}
+
+#[test]
+fn the_event_channel_reports_captures_and_promotions() {
+    let saved: SavedGame = SavedGame {
+        minutes: 5,
+        start: String::from("4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 2"),
+        moves: Vec::new(),
+        position: String::new(),
+        white_clock: Duration::from_secs(300),
+        black_clock: Duration::from_secs(300),
+        draw_offer: None,
+        days_per_move: None,
+        started: None,
+        timestamps: Vec::new(),
+        result: None,
+        variant: None,
+    };
+    let mut game: GameManager =
+        GameManager::resume(&saved, scripted(&["e5d6", "b7b8n"]), scripted(&["Kf7"])).unwrap();
+    game.set_renderer(silent());
+    let events = game.events();
+    game.start_game();
+
+    let events: Vec<GameEvent> = events.try_iter().collect();
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::Captured { colour: PieceColour::White, piece_type: PieceType::Pawn, square: s }
+            if *s == square("d5")
+    )));
+    assert!(events.iter().any(|event| matches!(
+        event,
+        GameEvent::Promoted { colour: PieceColour::White, piece_type: PieceType::Knight, square: s }
+            if *s == square("b8")
+    )));
+    assert!(matches!(
+        events.last(),
+        Some(GameEvent::GameOver {
+            winner: Some(PieceColour::White),
+            ..
+        })
+    ));
+}
@@ -0,0 +1,218 @@
+use chess::gif::{self, GifOptions, Theme};
+use chess::{Board, PieceColour};
+
+/// What a decoder makes of a GIF: its size, palette and frames, with how often its LZW codes
+/// were reset and how wide they grew.
+struct Decoded {
+    width: u16,
+    height: u16,
+    palette: Vec<[u8; 3]>,
+    /// Each frame's delay in hundredths of a second and its palette indices, row by row.
+    frames: Vec<(u16, Vec<u8>)>,
+    clears: usize,
+    widest_code: u8,
+}
+
+fn le(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+/// Reads the GIF block by block, panicking on anything a decoder would reject.
+fn decode(bytes: &[u8]) -> Decoded {
+    assert_eq!(&bytes[..6], b"GIF89a");
+    let (width, height): (u16, u16) = (le(bytes, 6), le(bytes, 8));
+    let packed: u8 = bytes[10];
+    assert_ne!(packed & 0x80, 0, "no global colour table");
+    let colours: usize = 2 << (packed & 7);
+    let palette: Vec<[u8; 3]> = bytes[13..13 + colours * 3]
+        .chunks(3)
+        .map(|colour| [colour[0], colour[1], colour[2]])
+        .collect();
+    let mut decoded: Decoded = Decoded {
+        width,
+        height,
+        palette,
+        frames: Vec::new(),
+        clears: 0,
+        widest_code: 0,
+    };
+
+    let mut at: usize = 13 + colours * 3;
+    let mut delay: u16 = 0;
+    loop {
+        match bytes[at] {
+            0x21 => {
+                let label: u8 = bytes[at + 1];
+                let (data, next): (Vec<u8>, usize) = sub_blocks(bytes, at + 2);
+                if label == 0xf9 {
+                    delay = le(&data, 1);
+                }
+                at = next;
+            }
+            0x2c => {
+                assert_eq!((le(bytes, at + 5), le(bytes, at + 7)), (width, height));
+                assert_eq!(bytes[at + 9] & 0x80, 0, "unexpected local colour table");
+                let minimum_code_size: u8 = bytes[at + 10];
+                let (data, next): (Vec<u8>, usize) = sub_blocks(bytes, at + 11);
+                let pixels: Vec<u8> = lzw(&data, minimum_code_size, &mut decoded);
+                decoded.frames.push((delay, pixels));
+                at = next;
+            }
+            0x3b => {
+                assert_eq!(at + 1, bytes.len(), "bytes after the trailer");
+                return decoded;
+            }
+            other => panic!("unknown block {:#x} at {}", other, at),
+        }
+    }
+}
+
+/// The data of the sub-blocks starting at `at`, and where the next block starts.
+fn sub_blocks(bytes: &[u8], mut at: usize) -> (Vec<u8>, usize) {
+    let mut data: Vec<u8> = Vec::new();
+    while bytes[at] != 0 {
+        let length: usize = bytes[at] as usize;
+        data.extend_from_slice(&bytes[at + 1..at + 1 + length]);
+        at += 1 + length;
+    }
+    (data, at + 1)
+}
+
+/// A plain LZW decoder as the GIF specification describes it.
+fn lzw(data: &[u8], minimum_code_size: u8, decoded: &mut Decoded) -> Vec<u8> {
+    let clear: usize = 1 << minimum_code_size;
+    let end: usize = clear + 1;
+    let initial: Vec<Vec<u8>> = (0..=end).map(|code| vec![code as u8]).collect();
+    let mut table: Vec<Vec<u8>> = initial.clone();
+    let mut code_size: u8 = minimum_code_size + 1;
+    let mut previous: Option<Vec<u8>> = None;
+    let mut pixels: Vec<u8> = Vec::new();
+    let mut bit: usize = 0;
+    loop {
+        let code: usize = (0..code_size as usize)
+            .map(|i| ((data[(bit + i) / 8] >> ((bit + i) % 8)) as usize & 1) << i)
+            .sum();
+        bit += code_size as usize;
+        decoded.widest_code = decoded.widest_code.max(code_size);
+        if code == clear {
+            decoded.clears += 1;
+            table = initial.clone();
+            code_size = minimum_code_size + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            return pixels;
+        }
+        let entry: Vec<u8> = match (table.get(code), &previous) {
+            (Some(entry), _) => entry.clone(),
+            // the code being defined by this very step
+            (None, Some(previous)) if code == table.len() => {
+                let mut entry: Vec<u8> = previous.clone();
+                entry.push(previous[0]);
+                entry
+            }
+            _ => panic!("code {} is not in the table of {}", code, table.len()),
+        };
+        pixels.extend_from_slice(&entry);
+        if let Some(mut previous) = previous.take() {
+            if table.len() < 4096 {
+                previous.push(entry[0]);
+                table.push(previous);
+            }
+        }
+        previous = Some(entry);
+        if table.len() == 1 << code_size && code_size < 12 {
+            code_size += 1;
+        }
+    }
+}
+
+/// The start position and the one after 1. e4.
+fn boards() -> Vec<Board> {
+    let start: Board = Board::new();
+    let mut after: Board = start.clone();
+    after.play_notation(PieceColour::White, "e4").unwrap();
+    vec![start, after]
+}
+
+/// The palette index of the top left pixel of every square, ranks from the top of the image,
+/// which no piece reaches.
+fn square_corners(decoded: &Decoded, pixels: &[u8]) -> Vec<Vec<u8>> {
+    let square: usize = decoded.width as usize / 8;
+    (0..8)
+        .map(|row| {
+            (0..8)
+                .map(|column| pixels[row * square * decoded.width as usize + column * square])
+                .collect()
+        })
+        .collect()
+}
+
+/// The square colours with White at the bottom, 0 for light and 1 for dark, with 2 and 3 for
+/// the highlighted e2 and e4 when `highlighted`.
+fn expected_corners(highlighted: bool) -> Vec<Vec<u8>> {
+    (0..8)
+        .map(|row| {
+            (0..8)
+                .map(|column| {
+                    let dark: u8 = ((row + column) % 2 == 1) as u8;
+                    let last_move: bool = column == 4 && (row == 4 || row == 6);
+                    if highlighted && last_move {
+                        dark + 2
+                    } else {
+                        dark
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn a_replay_has_one_frame_per_position_and_a_trailer() {
+    let options: GifOptions = GifOptions {
+        delay: 500,
+        size: 100,
+        theme: Theme::from_name("green").unwrap(),
+        bottom: PieceColour::White,
+    };
+    let decoded: Decoded = decode(&gif::to_gif(&boards(), &options));
+    // rounded down to a whole number of pixels per square
+    assert_eq!((decoded.width, decoded.height), (96, 96));
+    assert_eq!(decoded.palette.len(), 8);
+    assert_eq!(decoded.palette[0], [0xee, 0xee, 0xd2]);
+    assert_eq!(decoded.palette[1], [0x76, 0x96, 0x56]);
+
+    // the last position is held longer
+    let delays: Vec<u16> = decoded.frames.iter().map(|(delay, _)| *delay).collect();
+    assert_eq!(delays, vec![50, 150]);
+    for (_, pixels) in &decoded.frames {
+        assert_eq!(pixels.len(), 96 * 96);
+        assert!(pixels.iter().all(|&colour| colour < 7));
+    }
+    assert_eq!(
+        square_corners(&decoded, &decoded.frames[0].1),
+        expected_corners(false)
+    );
+    assert_eq!(
+        square_corners(&decoded, &decoded.frames[1].1),
+        expected_corners(true)
+    );
+    assert_ne!(decoded.frames[0].1, decoded.frames[1].1);
+}
+
+#[test]
+fn large_boards_widen_the_codes_and_reset_the_table() {
+    let options: GifOptions = GifOptions {
+        size: 1200,
+        ..GifOptions::default()
+    };
+    let decoded: Decoded = decode(&gif::to_gif(&boards()[1..], &options));
+    assert_eq!(decoded.widest_code, 12);
+    // one clear code opens each frame, the rest come from a full table
+    assert!(decoded.clears > 2, "only {} clear codes", decoded.clears);
+    let pixels: &[u8] = &decoded.frames[0].1;
+    assert_eq!(pixels.len(), 1200 * 1200);
+    assert_eq!(square_corners(&decoded, pixels), expected_corners(true));
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use chess::network::{RemoteLink, RemotePlayer};
+use chess::player::ScriptedPlayer;
+use chess::{GameManager, GameResult, PieceColour, Player, Termination};
+use common::{scripted, silent, Away};
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port()
+}
+
+/// Plays White's moves on a host and Black's on a client connected to it over 127.0.0.1,
+/// returning the result each end saw.
+fn play(white: &[&str], black: &[&str]) -> (GameResult, GameResult) {
+    play_with(white, scripted(black), None)
+}
+
+/// Like `play`, with any player for Black and, if given, the time Black has on the host's
+/// clock.
+fn play_with(
+    white: &[&str],
+    black: Box<dyn Player>,
+    black_time: Option<Duration>,
+) -> (GameResult, GameResult) {
+    let port: u16 = free_port();
+    let white: Vec<String> = white.iter().map(|notation| notation.to_string()).collect();
+    let host = thread::spawn(move || {
+        let remote: RemotePlayer = RemotePlayer::host(port, 5, silent()).unwrap();
+        let link: RemoteLink = remote.link().unwrap();
+        let mut game: GameManager =
+            GameManager::new(5, Box::new(ScriptedPlayer::new(white)), Box::new(remote));
+        if let Some(black_time) = black_time {
+            game.clocks().1.set_remaining(black_time);
+        }
+        game.set_renderer(silent());
+        link.attach(&mut game).unwrap();
+        game.start_game()
+    });
+
+    let (remote, minutes): (RemotePlayer, u64) = connect(port);
+    assert_eq!(minutes, 5);
+    assert_eq!(remote.colour(), PieceColour::White);
+    let client: GameResult = play_client(remote, black);
+    (host.join().unwrap(), client)
+}
+
+fn connect(port: u16) -> (RemotePlayer, u64) {
+    let address: String = format!("127.0.0.1:{}", port);
+    loop {
+        match RemotePlayer::connect(&address, silent()) {
+            Ok(connected) => break connected,
+            // the host is not listening yet
+            Err(_) => thread::sleep(Duration::from_millis(20)),
+        }
+    }
+}
+
+fn play_client(remote: RemotePlayer, black: Box<dyn Player>) -> GameResult {
+    let link: RemoteLink = remote.link().unwrap();
+    let mut game: GameManager = GameManager::new(5, Box::new(remote), black);
+    game.set_renderer(silent());
+    link.attach(&mut game).unwrap();
+    game.start_game()
+}
+
+#[test]
+fn a_game_is_played_over_localhost() {
+    let (host, client) = play(&["f3", "g4"], &["e5", "Qh4"]);
+    for result in [&host, &client] {
+        assert_eq!(result.winner, Some(PieceColour::Black));
+        assert_eq!(result.termination, Termination::Checkmate);
+        let moves: Vec<&str> = result
+            .moves
+            .iter()
+            .map(|record| record.san.as_str())
+            .collect();
+        assert_eq!(moves, vec!["f3", "e5", "g4", "Qh4#"]);
+    }
+    assert_eq!(host.fen(), client.fen());
+}
+
+#[test]
+fn saving_adjourns_the_game_at_both_ends() {
+    let path: String = env::temp_dir()
+        .join(format!("chess-network-adjourn-{}.txt", free_port()))
+        .to_string_lossy()
+        .into_owned();
+    let (host, client) = play(&["e4", &format!("save {}", path)], &["e5"]);
+    for result in [&host, &client] {
+        assert_eq!(result.winner, None);
+        assert_eq!(result.termination, Termination::Adjourned);
+        assert_eq!(result.moves.len(), 2);
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn the_hosts_flag_fall_ends_the_game_at_both_ends() {
+    let (host, client) = play_with(
+        &["e4"],
+        Box::new(Away::new()),
+        Some(Duration::from_millis(300)),
+    );
+    for result in [&host, &client] {
+        assert_eq!(result.winner, Some(PieceColour::White));
+        assert_eq!(result.termination, Termination::Timeout);
+        assert_eq!(result.moves.len(), 1);
+    }
+}
+
+#[test]
+fn an_illegal_move_from_the_other_program_abandons_the_game() {
+    let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port: u16 = listener.local_addr().unwrap().port();
+    let host = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        writeln!(stream, "chess 5").unwrap();
+        writeln!(stream, "move e2e5").unwrap();
+        stream
+    });
+    let (remote, _): (RemotePlayer, u64) = connect(port);
+    // held open until the client's game is over, so that only the move can end it
+    let _stream: TcpStream = host.join().unwrap();
+    let client: GameResult = play_client(remote, scripted(&[]));
+    assert_eq!(client.winner, Some(PieceColour::Black));
+    assert_eq!(client.termination, Termination::Abandonment);
+}
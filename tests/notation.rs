@@ -0,0 +1,115 @@
+use chess::pgn::{self, MoveRecord};
+use chess::{Board, CoordinateMove, Move, PieceColour, PieceType, Position};
+
+#[test]
+fn squares_and_coordinate_moves_round_trip() {
+    let e4: Position = Position::from_notation("e4").unwrap();
+    assert_eq!(e4, Position::new(4, 5));
+    assert_eq!(e4.to_string(), "e4");
+    assert!(Position::from_notation("i1").is_err());
+
+    let promotion: CoordinateMove = CoordinateMove::from_notation("a7a8n").unwrap();
+    assert_eq!(promotion.promotion, Some(PieceType::Knight));
+    assert_eq!(promotion.to_notation(), "a7a8n");
+    assert!(CoordinateMove::from_notation("a7a8k").is_err());
+}
+
+#[test]
+fn algebraic_moves_are_parsed() {
+    let movement: Move = Move::from_notation("Nbd7").unwrap();
+    assert_eq!(
+        movement,
+        Move::new(Position::new(7, 4), Some(2), None, PieceType::Knight)
+    );
+    assert!(Move::from_notation("Xe4").is_err());
+}
+
+#[test]
+fn fen_round_trips() {
+    for fen in [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+        "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 b - - 0 1",
+    ] {
+        let (board, turn) = Board::from_fen(fen).unwrap();
+        assert_eq!(board.to_fen(turn, 1), fen);
+    }
+    assert!(Board::from_fen("rnbqkbnr/pppppppp/8/8 w KQkq - 0 1").is_err());
+}
+
+#[test]
+fn moves_are_named_in_san() {
+    let (board, turn) = Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+    let castle: CoordinateMove = CoordinateMove::from_notation("e1g1").unwrap();
+    assert_eq!(board.san(turn, &castle), "O-O");
+    let check: CoordinateMove = CoordinateMove::from_notation("a1a8").unwrap();
+    assert_eq!(board.san(turn, &check), "Ra8+");
+
+    let mut board: Board = Board::new();
+    let moves: Vec<CoordinateMove> = board.legal_moves(PieceColour::White);
+    let mut names: Vec<String> = moves
+        .iter()
+        .map(|m| board.san(PieceColour::White, m))
+        .collect();
+    names.sort();
+    assert!(names.contains(&String::from("Nf3")));
+    assert!(names.contains(&String::from("e4")));
+}
+
+#[test]
+fn pgn_is_written_and_read_back() {
+    let mut board: Board = Board::new();
+    let mut turn: PieceColour = PieceColour::White;
+    let mut records: Vec<MoveRecord> = Vec::new();
+    for notation in ["e2e4", "e7e5", "g1f3", "b8c6", "f1b5"] {
+        let coordinate_move: CoordinateMove = CoordinateMove::from_notation(notation).unwrap();
+        let san: String = board.san(turn, &coordinate_move);
+        board.play_coordinate_move(turn, &coordinate_move);
+        records.push(MoveRecord {
+            coordinate_move,
+            san,
+            draw_offer: false,
+        });
+        turn = turn.opponent();
+    }
+
+    let text: String = pgn::to_pgn(&records, "*");
+    assert!(text.contains("1. e4 e5 2. Nf3 Nc6 3. Bb5"));
+
+    let game = pgn::read_pgn(&text).unwrap();
+    let played: Vec<CoordinateMove> = records.iter().map(|r| r.coordinate_move).collect();
+    assert_eq!(game.moves, played);
+    let (last, last_turn) = game.positions().pop().unwrap();
+    assert_eq!(last.to_fen(last_turn, 3), board.to_fen(turn, 3));
+}
+
+#[test]
+fn pgn_comments_and_variations_are_skipped() {
+    let text: &str =
+        "[Event \"Test\"]\n\n1. e4 {best by test} e5 (1... c5 2. Nf3) 2. Nf3! $1 Nc6 1-0\n";
+    let game = pgn::read_pgn(text).unwrap();
+    assert_eq!(game.moves.len(), 4);
+    assert!(pgn::read_pgn("1. e4 e4").is_err());
+}
+
+#[test]
+fn draw_offers_are_written_as_an_annotation_in_pgn() {
+    let mut board: Board = Board::new();
+    let mut turn: PieceColour = PieceColour::White;
+    let mut records: Vec<MoveRecord> = Vec::new();
+    for (notation, draw_offer) in [("e2e4", false), ("e7e5", true), ("g1f3", false)] {
+        let coordinate_move: CoordinateMove = CoordinateMove::from_notation(notation).unwrap();
+        let san: String = board.san(turn, &coordinate_move);
+        board.play_coordinate_move(turn, &coordinate_move);
+        records.push(MoveRecord {
+            coordinate_move,
+            san,
+            draw_offer,
+        });
+        turn = turn.opponent();
+    }
+
+    let text: String = pgn::to_pgn(&records, "*");
+    assert!(text.contains("1. e4 e5 = 2. Nf3 *"));
+    assert_eq!(pgn::read_pgn(&text).unwrap().moves.len(), 3);
+}
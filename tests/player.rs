@@ -0,0 +1,46 @@
+use chess::player::PlayerAction;
+
+fn offers_draw(line: &str) -> Option<(String, bool)> {
+    match PlayerAction::parse(line) {
+        PlayerAction::Move {
+            notation,
+            draw_offer,
+        } => Some((notation, draw_offer)),
+        _ => None,
+    }
+}
+
+#[test]
+fn a_draw_offer_follows_the_move() {
+    assert_eq!(offers_draw("Nf3 draw"), Some((String::from("Nf3"), true)));
+    assert_eq!(offers_draw("Nf3\tdraw"), Some((String::from("Nf3"), true)));
+    assert_eq!(offers_draw("Nf3 (=)"), Some((String::from("Nf3"), true)));
+    assert_eq!(offers_draw("e4 DRAW"), Some((String::from("e4"), true)));
+    assert_eq!(offers_draw("Nf3"), Some((String::from("Nf3"), false)));
+}
+
+#[test]
+fn draw_must_be_set_apart_from_the_move() {
+    assert_eq!(offers_draw("e4draw"), Some((String::from("e4draw"), false)));
+    assert_eq!(offers_draw("draw"), Some((String::from("draw"), false)));
+}
+
+#[test]
+fn commands_with_an_argument_are_read_in_any_case() {
+    assert!(matches!(
+        PlayerAction::parse("SAVE game.txt"),
+        PlayerAction::Save(path) if path == "game.txt"
+    ));
+    assert!(matches!(
+        PlayerAction::parse("Svg board.svg"),
+        PlayerAction::ExportSvg(path) if path == "board.svg"
+    ));
+    assert!(matches!(
+        PlayerAction::parse("MOVES e2"),
+        PlayerAction::ShowMoves(square) if square.to_string() == "e2"
+    ));
+    assert!(matches!(
+        PlayerAction::parse("sav"),
+        PlayerAction::Move { .. }
+    ));
+}
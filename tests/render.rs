@@ -0,0 +1,171 @@
+mod common;
+
+use chess::render::{AnsiRenderer, Frame, Line, Orientation, PlainRenderer, Renderer, ESC};
+use chess::style::{PieceSet, SquareStyle, Style};
+use chess::{Board, PieceColour, Position};
+use common::square;
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Everything written to it, kept after the renderer that owns it is gone.
+#[derive(Clone, Default)]
+struct Captured(Arc<Mutex<Vec<u8>>>);
+
+impl Captured {
+    fn text(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+impl Write for Captured {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+const ASCII: Style = Style {
+    pieces: PieceSet::ASCII,
+    squares: SquareStyle::Dots,
+};
+
+fn frame(board: &Board) -> Frame<'_> {
+    Frame {
+        board,
+        turn: PieceColour::White,
+        clocks: Some((Duration::from_millis(299_700), Duration::from_secs(300))),
+        destinations: &[],
+        moves: &[],
+        notes: (String::new(), String::new()),
+    }
+}
+
+const START: &str = concat!(
+    "Black 5:0.0\n",
+    "  ----------------\n",
+    "8|r n b q k b n r |\n",
+    "7|p p p p p p p p |\n",
+    "6|  .   .   .   . |\n",
+    "5|.   .   .   .   |\n",
+    "4|  .   .   .   . |\n",
+    "3|.   .   .   .   |\n",
+    "2|P P P P P P P P |\n",
+    "1|R N B Q K B N R |\n",
+    "  ----------------\n",
+    "  a b c d e f g h \n",
+    "White 4:59.7\n",
+);
+
+#[test]
+fn the_plain_renderer_writes_frames_and_lines_as_text() {
+    let output: Captured = Captured::default();
+    let mut renderer: PlainRenderer =
+        PlainRenderer::new(Box::new(output.clone()), Orientation::White, ASCII);
+    renderer.frame(&frame(&Board::new()));
+    assert_eq!(output.text(), START);
+
+    // ticks would fill a log, so only frames show the clocks
+    renderer.tick(PieceColour::White, Duration::from_secs(200));
+    renderer.line(Line::Prompt, "your move:");
+    assert_eq!(output.text(), format!("{}your move:\n", START));
+}
+
+#[test]
+fn the_ansi_renderer_draws_the_frame_and_clocks_in_place() {
+    let output: Captured = Captured::default();
+    let mut renderer: AnsiRenderer =
+        AnsiRenderer::new(Box::new(output.clone()), Orientation::White, ASCII);
+    renderer.frame(&frame(&Board::new()));
+    let text: String = output.text();
+    // clears the screen, with Black's clock above the board and White's below it
+    assert!(text.starts_with(&format!("{}2J{}H5:0.0\n", ESC, ESC)));
+    assert!(text.contains("8|r n b q k b n r |\n"));
+    assert!(text.contains("1|R N B Q K B N R |\n"));
+    assert!(text.ends_with(&format!("{}13;0H4:59.7\n", ESC)));
+
+    let drawn: usize = text.len();
+    renderer.tick(PieceColour::White, Duration::from_secs(61));
+    assert_eq!(
+        &output.text()[drawn..],
+        format!("{}s{}13;0H{}2K1:1.0\n{}u", ESC, ESC, ESC, ESC)
+    );
+}
+
+#[test]
+fn black_at_the_bottom_turns_the_board_around() {
+    let output: Captured = Captured::default();
+    let mut renderer: PlainRenderer =
+        PlainRenderer::new(Box::new(output.clone()), Orientation::Black, ASCII);
+    renderer.frame(&frame(&Board::new()));
+    let text: String = output.text();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines[0], "White 4:59.7");
+    assert_eq!(lines[2], "1|R N B K Q B N R |");
+    assert_eq!(lines[9], "8|r n b k q b n r |");
+    assert_eq!(lines[11], "  h g f e d c b a ");
+    assert_eq!(lines[12], "Black 5:0.0");
+}
+
+#[test]
+fn auto_orientation_puts_the_side_to_move_at_the_bottom() {
+    let output: Captured = Captured::default();
+    let mut renderer: AnsiRenderer =
+        AnsiRenderer::new(Box::new(output.clone()), Orientation::Auto, ASCII);
+    let board: Board = Board::new();
+    let mut black_to_move: Frame = frame(&board);
+    black_to_move.turn = PieceColour::Black;
+    renderer.frame(&black_to_move);
+    assert!(output.text().contains(
+        "1|R N B K Q B N R |
+"
+    ));
+
+    // the clock of the side at the bottom is drawn below the board, the other above it
+    let drawn: usize = output.text().len();
+    renderer.tick(PieceColour::Black, Duration::from_secs(10));
+    renderer.tick(PieceColour::White, Duration::from_secs(20));
+    assert_eq!(
+        &output.text()[drawn..],
+        format!(
+            "{}s{}13;0H{}2K0:10.0\n{}u{}s{}1;0H{}2K0:20.0\n{}u",
+            ESC, ESC, ESC, ESC, ESC, ESC, ESC, ESC
+        )
+    );
+}
+
+/// The ANSI frame of `board` with White to move, showing `destinations`.
+fn highlighted(board: &Board, destinations: &[Position]) -> String {
+    let output: Captured = Captured::default();
+    let mut renderer: AnsiRenderer =
+        AnsiRenderer::new(Box::new(output.clone()), Orientation::White, ASCII);
+    let mut frame: Frame = frame(board);
+    frame.destinations = destinations;
+    renderer.frame(&frame);
+    output.text()
+}
+
+#[test]
+fn the_last_move_a_checked_king_and_destinations_are_highlighted() {
+    let mut board: Board = Board::new();
+    for (turn, notation) in [(PieceColour::White, "f3"), (PieceColour::Black, "e5")] {
+        board.play_notation(turn, notation).unwrap();
+    }
+    let text: String = highlighted(&board, &[square("g3"), square("g4")]);
+    // e7 and e5, the last move
+    assert!(text.contains(&format!("7|p p p p {}43m  {}0mp p p |", ESC, ESC)));
+    assert!(text.contains(&format!("5|.   .   {}43mp {}0m  .   |", ESC, ESC)));
+    assert!(text.contains(&format!("3|.   .   . P {}42m  {}0m  |", ESC, ESC)));
+    assert!(text.contains(&format!("4|  .   .   . {}42m  {}0m. |", ESC, ESC)));
+    assert_eq!(text.matches("41m").count(), 0);
+
+    board.play_notation(PieceColour::White, "g4").unwrap();
+    board.play_notation(PieceColour::Black, "Qh4").unwrap();
+    let text: String = highlighted(&board, &[]);
+    assert!(text.contains(&format!("1|R N B Q {}41mK {}0mB N R |", ESC, ESC)));
+    assert_eq!(text.matches("43m").count(), 2);
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use chess::game::GameEvent;
+use chess::player::{Notification, PlayerAction};
+use chess::render::{Frame, Line, Renderer};
+use chess::result::{EXIT_BLACK_WON, EXIT_DRAW, EXIT_WHITE_WON};
+use chess::save::SavedGame;
+use chess::{Board, GameManager, GameResult, PieceColour, PieceType, Player, Termination};
+use common::{scripted, silent, Away};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Thinks for longer than its clock allows, then plays e4.
+struct Slow;
+
+impl Player for Slow {
+    fn request_move(&mut self, _board: &Board, _colour: PieceColour) -> PlayerAction {
+        thread::sleep(Duration::from_millis(800));
+        PlayerAction::parse("e4")
+    }
+
+    fn choose_promotion(&mut self, _board: &Board, _colour: PieceColour) -> PieceType {
+        PieceType::Queen
+    }
+
+    fn notify(&mut self, _notification: &Notification) {}
+}
+
+/// Counts the clock updates drawn.
+struct Ticks(Arc<Mutex<u32>>);
+
+impl Renderer for Ticks {
+    fn frame(&mut self, _frame: &Frame) {}
+    fn tick(&mut self, _colour: PieceColour, _remaining: Duration) {
+        *self.0.lock().unwrap() += 1;
+    }
+    fn line(&mut self, _line: Line, _text: &str) {}
+}
+
+fn play(mut game: GameManager) -> GameResult {
+    game.set_renderer(silent());
+    game.start_game()
+}
+
+fn from_fen(fen: &str, white: &[&str], black: &[&str]) -> GameResult {
+    let saved: SavedGame = SavedGame {
+        minutes: 5,
+        start: fen.to_string(),
+        moves: Vec::new(),
+        position: String::new(),
+        white_clock: Duration::from_secs(300),
+        black_clock: Duration::from_secs(300),
+        draw_offer: None,
+        days_per_move: None,
+        started: None,
+        timestamps: Vec::new(),
+        result: None,
+        variant: None,
+    };
+    play(GameManager::resume(&saved, scripted(white), scripted(black)).unwrap())
+}
+
+#[test]
+fn checkmate_gives_the_winner_final_position_and_moves() {
+    let result: GameResult = play(GameManager::new(
+        5,
+        scripted(&["f3", "g4"]),
+        scripted(&["e5", "Qh4"]),
+    ));
+    assert_eq!(result.winner, Some(PieceColour::Black));
+    assert_eq!(result.termination, Termination::Checkmate);
+    assert_eq!(result.score(), "0-1");
+    assert_eq!(result.exit_code(), EXIT_BLACK_WON);
+    assert_eq!(result.turn, PieceColour::White);
+    assert_eq!(
+        result.fen(),
+        "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3"
+    );
+    let moves: Vec<&str> = result
+        .moves
+        .iter()
+        .map(|record| record.san.as_str())
+        .collect();
+    assert_eq!(moves, vec!["f3", "e5", "g4", "Qh4#"]);
+    assert_eq!(result.to_string(), "Black won by checkmate");
+}
+
+#[test]
+fn running_out_of_moves_resigns() {
+    let result: GameResult = play(GameManager::new(5, scripted(&["e4"]), scripted(&["e5"])));
+    assert_eq!(result.winner, Some(PieceColour::Black));
+    assert_eq!(result.termination, Termination::Resignation);
+    assert_eq!(result.moves.len(), 2);
+}
+
+#[test]
+fn accepting_a_draw_offer_draws() {
+    let result: GameResult = play(GameManager::new(
+        5,
+        scripted(&["e4 draw"]),
+        scripted(&["accept"]),
+    ));
+    assert_eq!(result.winner, None);
+    assert_eq!(result.termination, Termination::Agreement);
+    assert_eq!(result.score(), "1/2-1/2");
+    assert_eq!(result.exit_code(), EXIT_DRAW);
+}
+
+#[test]
+fn stalemate_and_insufficient_material_draw() {
+    let result: GameResult = from_fen("k7/8/2Q5/8/8/8/8/7K w - - 0 1", &["Qb6"], &[]);
+    assert_eq!(result.termination, Termination::Stalemate);
+
+    let result: GameResult = from_fen("4k3/8/8/8/8/8/3r4/1N2K3 w - - 0 1", &["Nd2"], &[]);
+    assert_eq!(result.winner, None);
+    assert_eq!(result.termination, Termination::InsufficientMaterial);
+    assert_eq!(result.to_string(), "Draw by insufficient material");
+}
+
+#[test]
+fn a_resigned_endgame_still_reports_the_position() {
+    let result: GameResult = from_fen("4k3/8/8/8/8/8/8/R3K3 w - - 0 1", &["Ra7"], &[]);
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.exit_code(), EXIT_WHITE_WON);
+    assert_eq!(result.fen(), "4k3/R7/8/8/8/8/8/4K3 b - - 1 1");
+}
+
+#[test]
+fn running_out_of_time_loses_once_the_player_returns() {
+    let game: GameManager = GameManager::new(5, Box::new(Slow), scripted(&["e5"]));
+    let (white_clock, _) = game.clocks();
+    white_clock.set_remaining(Duration::from_millis(200));
+    let result: GameResult = play(game);
+    assert_eq!(result.winner, Some(PieceColour::Black));
+    assert_eq!(result.termination, Termination::Timeout);
+    assert_eq!(result.exit_code(), EXIT_BLACK_WON);
+    // the move that came too late is not played
+    assert!(result.moves.is_empty());
+    assert_eq!(result.to_string(), "Black won by timeout");
+}
+
+#[test]
+fn a_flag_fall_stops_a_player_waiting_for_input() {
+    let mut game: GameManager = GameManager::new(5, scripted(&["e4"]), Box::new(Away::new()));
+    let (_, black_clock) = game.clocks();
+    black_clock.set_remaining(Duration::from_millis(300));
+    let events: Receiver<GameEvent> = game.events();
+    let started: Instant = Instant::now();
+    let result: GameResult = play(game);
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.termination, Termination::Timeout);
+    assert_eq!(result.moves.len(), 1);
+
+    let events: Vec<GameEvent> = events.try_iter().collect();
+    assert!(matches!(
+        events.as_slice(),
+        [
+            ..,
+            GameEvent::FlagFell(PieceColour::Black),
+            GameEvent::GameOver {
+                winner: Some(PieceColour::White),
+                termination: Termination::Timeout,
+            }
+        ]
+    ));
+}
+
+#[test]
+fn the_clock_stops_when_the_game_ends() {
+    let ticks: Arc<Mutex<u32>> = Arc::new(Mutex::new(0));
+    let mut game: GameManager = GameManager::new(5, Box::new(Slow), scripted(&[]));
+    game.set_renderer(Arc::new(Mutex::new(Ticks(Arc::clone(&ticks)))));
+    let result: GameResult = game.start_game();
+    assert_eq!(result.termination, Termination::Resignation);
+    let shown: u32 = *ticks.lock().unwrap();
+    assert!(shown > 0);
+    thread::sleep(Duration::from_millis(500));
+    assert_eq!(*ticks.lock().unwrap(), shown);
+}
@@ -0,0 +1,31 @@
+use chess::save::SavedGame;
+
+#[test]
+fn correspondence_and_variant_games_round_trip() {
+    let text: &str = "chess-save 1
+time-control 0
+variant Three-check
+start 4k3/8/8/8/8/8/8/Q3K3 w - - 3+3 0 1
+moves a1a4 e8f7
+position 8/5k2/8/8/Q7/8/8/4K3 w - - 2+3 2 2
+days-per-move 3
+started 1792310400
+timestamps 1792314000 1792400000
+draw-offer none
+result 1-0 resignation
+";
+    let saved: SavedGame = SavedGame::parse(text).unwrap();
+    assert_eq!(saved.variant.as_deref(), Some("Three-check"));
+    assert_eq!(saved.days_per_move, Some(3));
+    assert_eq!(saved.timestamps, vec![1792314000, 1792400000]);
+    assert_eq!(saved.to_text(), text);
+    assert!(saved.replay().is_ok());
+}
+
+#[test]
+fn broken_saves_are_rejected() {
+    assert!(SavedGame::parse("not a save").is_err());
+    assert!(SavedGame::parse("chess-save 1\nmoves e2e4\n").is_err());
+    assert!(SavedGame::parse("chess-save 1\nstart x\nwhite-clock soon\n").is_err());
+    assert!(SavedGame::parse("chess-save 1\nstart x\ncolour blue\n").is_err());
+}
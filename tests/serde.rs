@@ -0,0 +1,60 @@
+#![cfg(feature = "serde")]
+
+use chess::player::EnginePlayer;
+use chess::save::SavedGame;
+use chess::{
+    Board, CoordinateMove, GameManager, Move, MoveResult, Piece, PieceColour, PieceType, Position,
+};
+use std::time::Duration;
+
+#[test]
+fn moves_and_squares_round_trip_through_json() {
+    let e4: Position = Position::from_notation("e4").unwrap();
+    let json: String = serde_json::to_string(&e4).unwrap();
+    assert_eq!(json, r#"{"row":4,"column":5}"#);
+    assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), e4);
+
+    let movement: Move = Move::from_notation("Nbd7").unwrap();
+    let json: String = serde_json::to_string(&movement).unwrap();
+    assert_eq!(serde_json::from_str::<Move>(&json).unwrap(), movement);
+
+    let result: MoveResult = MoveResult::PromotionAvailable(Position::new(8, 1));
+    let json: String = serde_json::to_string(&result).unwrap();
+    assert_eq!(serde_json::from_str::<MoveResult>(&json).unwrap(), result);
+
+    let colour: String = serde_json::to_string(&PieceColour::Black).unwrap();
+    assert_eq!(colour, r#""Black""#);
+}
+
+#[test]
+fn pieces_round_trip_through_json() {
+    let board: Board = Board::new();
+    let knight: &Piece = board.piece_at(Position::new(1, 2)).unwrap();
+    let json: String = serde_json::to_string(knight).unwrap();
+    let read: Piece = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.piece_type, PieceType::Knight);
+    assert_eq!(read.colour, PieceColour::White);
+    assert_eq!(read.position, knight.position);
+}
+
+#[test]
+fn a_game_record_round_trips_through_json() {
+    let game: GameManager = GameManager::new(
+        5,
+        Box::new(EnginePlayer::new()),
+        Box::new(EnginePlayer::new()),
+    );
+    let mut saved: SavedGame = game.saved_game();
+    saved.moves = vec![
+        (CoordinateMove::from_notation("e2e4").unwrap(), false),
+        (CoordinateMove::from_notation("e7e5").unwrap(), true),
+    ];
+    saved.position = String::from("rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 0 2");
+    saved.white_clock = Duration::from_millis(4500);
+    saved.draw_offer = Some(PieceColour::Black);
+
+    let json: String = serde_json::to_string(&saved).unwrap();
+    let read: SavedGame = serde_json::from_str(&json).unwrap();
+    assert_eq!(read.to_text(), saved.to_text());
+    assert!(read.replay().is_ok());
+}
@@ -0,0 +1,209 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+/// A `chess-server` on a port of its own, stopped when the test ends.
+struct Server {
+    process: Child,
+    port: u16,
+}
+
+impl Server {
+    fn start() -> Server {
+        let port: u16 = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let process: Child = Command::new(env!("CARGO_BIN_EXE_chess-server"))
+            .arg(port.to_string())
+            .stdout(Stdio::null())
+            .spawn()
+            .unwrap();
+        Server { process, port }
+    }
+
+    fn connect(&self) -> Client {
+        let stream: TcpStream = loop {
+            match TcpStream::connect(("127.0.0.1", self.port)) {
+                Ok(stream) => break stream,
+                // the server is not listening yet
+                Err(_) => thread::sleep(Duration::from_millis(20)),
+            }
+        };
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        Client {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn send(&mut self, line: &str) {
+        writeln!(self.writer, "{}", line).unwrap();
+    }
+
+    fn receive(&mut self) -> String {
+        let mut line: String = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+
+    /// The words of the next line, which must start with `command`.
+    fn expect(&mut self, command: &str) -> Vec<String> {
+        let line: String = self.receive();
+        let words: Vec<String> = line.split_whitespace().map(String::from).collect();
+        assert_eq!(words.first().map(String::as_str), Some(command), "{}", line);
+        words
+    }
+}
+
+/// Seats `white` and `black` at a new game, returning its id and the reconnect keys.
+fn start_game(white: &mut Client, black: &mut Client) -> (String, String, String) {
+    white.send("seek 5 white");
+    let seek: String = white.expect("seeking")[1].clone();
+    black.send(&format!("join {}", seek));
+    let started: Vec<String> = white.expect("started");
+    assert_eq!(started[2], "white");
+    let black_started: Vec<String> = black.expect("started");
+    assert_eq!(black_started[2], "black");
+    assert_eq!(started[1], black_started[1]);
+    (
+        started[1].clone(),
+        started[3].clone(),
+        black_started[3].clone(),
+    )
+}
+
+#[test]
+fn players_seek_join_and_play_to_mate() {
+    let server: Server = Server::start();
+    let mut white: Client = server.connect();
+    let mut black: Client = server.connect();
+    white.expect("hello");
+    black.expect("hello");
+    white.send("name alice");
+    black.send("name bob");
+
+    white.send("seek 5 white");
+    let seek: String = white.expect("seeking")[1].clone();
+    black.send("seeks");
+    assert_eq!(
+        black.expect("seek"),
+        ["seek", seek.as_str(), "alice", "5", "white"]
+    );
+    black.expect("end");
+    black.send(&format!("join {}", seek));
+    let started: Vec<String> = white.expect("started");
+    assert_eq!(started[2], "white");
+    assert_eq!(started[4..], ["alice", "bob", "5"]);
+    let game: String = started[1].clone();
+    black.expect("started");
+
+    for (mover, notation) in [(0, "f3"), (1, "e5"), (0, "g4"), (1, "Qh4")] {
+        let client: &mut Client = if mover == 0 { &mut white } else { &mut black };
+        client.send(&format!("move {} {}", game, notation));
+        for client in [&mut white, &mut black] {
+            assert_eq!(client.expect("moved")[1], game);
+        }
+    }
+    for client in [&mut white, &mut black] {
+        assert_eq!(client.receive(), format!("over {} 0-1 checkmate", game));
+    }
+}
+
+#[test]
+fn bad_commands_and_moves_are_errors() {
+    let server: Server = Server::start();
+    let mut white: Client = server.connect();
+    let mut black: Client = server.connect();
+    white.expect("hello");
+    black.expect("hello");
+    white.send("dance");
+    assert_eq!(white.receive(), "error unknown command");
+
+    let (game, _, _) = start_game(&mut white, &mut black);
+    black.send(&format!("move {} e5", game));
+    assert_eq!(black.receive(), "error it is not your turn");
+    white.send(&format!("move {} e5", game));
+    assert_eq!(white.receive(), "error this move is not legal");
+    white.send(&format!("accept {}", game));
+    assert_eq!(white.receive(), "error there is no draw offer to accept");
+}
+
+#[test]
+fn spectators_see_the_position_and_every_move() {
+    let server: Server = Server::start();
+    let mut white: Client = server.connect();
+    let mut black: Client = server.connect();
+    let mut spectator: Client = server.connect();
+    for client in [&mut white, &mut black, &mut spectator] {
+        client.expect("hello");
+    }
+    let (game, _, _) = start_game(&mut white, &mut black);
+    white.send(&format!("move {} e4", game));
+    white.expect("moved");
+    black.expect("moved");
+
+    spectator.send(&format!("watch {}", game));
+    let position: Vec<String> = spectator.expect("position");
+    assert_eq!(position[1], game);
+    assert_eq!(position.last().unwrap(), "e2e4");
+    black.send(&format!("move {} e5 draw", game));
+    let moved: Vec<String> = spectator.expect("moved");
+    assert_eq!(moved[2..4], ["e7e5", "e5"]);
+    assert_eq!(moved.last().unwrap(), "draw");
+    white.expect("moved");
+    black.expect("moved");
+
+    white.send(&format!("accept {}", game));
+    for client in [&mut white, &mut black, &mut spectator] {
+        assert_eq!(client.receive(), format!("over {} 1/2-1/2 agreement", game));
+    }
+}
+
+#[test]
+fn a_dropped_player_reconnects_with_their_key() {
+    let server: Server = Server::start();
+    let mut white: Client = server.connect();
+    let mut black: Client = server.connect();
+    white.expect("hello");
+    black.expect("hello");
+    let (game, _, black_key) = start_game(&mut white, &mut black);
+    white.send(&format!("move {} e4", game));
+    white.expect("moved");
+    drop(black);
+    assert_eq!(white.receive(), format!("left {} black", game));
+
+    let mut black: Client = server.connect();
+    black.expect("hello");
+    black.send(&format!("reconnect {} 0", game));
+    assert_eq!(black.receive(), "error wrong key");
+    black.send(&format!("reconnect {} {}", game, black_key));
+    assert_eq!(black.expect("started")[3], black_key);
+    assert_eq!(black.expect("position").last().unwrap(), "e2e4");
+    assert_eq!(black.receive(), format!("returned {} black", game));
+    assert_eq!(white.receive(), format!("returned {} black", game));
+
+    black.send(&format!("resign {}", game));
+    for client in [&mut white, &mut black] {
+        assert_eq!(client.receive(), format!("over {} 1-0 resignation", game));
+    }
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use chess::svg::{self, SvgOptions};
+use chess::{Board, PieceColour};
+use common::square;
+
+fn black_at_the_bottom() -> SvgOptions {
+    SvgOptions {
+        bottom: PieceColour::Black,
+        ..SvgOptions::default()
+    }
+}
+
+/// The `<text>` element drawing the white king at `x`, `y`.
+fn white_king(x: usize, y: usize) -> String {
+    format!(
+        "<text x=\"{}\" y=\"{}\" fill=\"#ffffff\" stroke=\"#000000\" stroke-width=\"1.2\">♚\u{FE0E}</text>",
+        x, y
+    )
+}
+
+fn label(x: usize, y: usize, text: &str) -> String {
+    format!("<text x=\"{}\" y=\"{}\">{}</text>", x, y, text)
+}
+
+fn highlight(x: usize, y: usize) -> String {
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"45\" height=\"45\" fill=\"#f7ec5a\" fill-opacity=\"0.5\"/>",
+        x, y
+    )
+}
+
+#[test]
+fn the_chosen_side_is_drawn_at_the_bottom() {
+    let board: Board = Board::new();
+    // e1 is the fifth square from the left on the bottom rank, inside a 20 pixel margin
+    let image: String = svg::to_svg(&board, &SvgOptions::default());
+    assert!(image.contains(&white_king(222, 359)));
+    // and the fourth from the left on the top rank when the board is turned around
+    let image: String = svg::to_svg(&board, &black_at_the_bottom());
+    assert!(image.contains(&white_king(177, 44)));
+}
+
+#[test]
+fn coordinates_follow_the_orientation_and_can_be_left_out() {
+    let board: Board = Board::new();
+    let image: String = svg::to_svg(&board, &SvgOptions::default());
+    assert!(image.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"400\""));
+    assert!(image.contains(&label(42, 390, "a")));
+    assert!(image.contains(&label(357, 390, "h")));
+    assert!(image.contains(&label(10, 357, "1")));
+    assert!(image.contains(&label(10, 42, "8")));
+
+    let image: String = svg::to_svg(&board, &black_at_the_bottom());
+    assert!(image.contains(&label(357, 390, "a")));
+    assert!(image.contains(&label(10, 42, "1")));
+
+    let options: SvgOptions = SvgOptions {
+        coordinates: false,
+        ..SvgOptions::default()
+    };
+    let image: String = svg::to_svg(&board, &options);
+    assert!(image.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"360\""));
+    assert!(!image.contains(&label(20, 370, "a")));
+    assert!(image.contains(&white_king(202, 339)));
+}
+
+#[test]
+fn the_last_move_is_highlighted_with_any_other_squares() {
+    let mut board: Board = Board::new();
+    let image: String = svg::to_svg(&board, &svg::with_last_move(&board, &SvgOptions::default()));
+    assert!(!image.contains("#f7ec5a"));
+
+    board.play_notation(PieceColour::White, "e4").unwrap();
+    let options: SvgOptions = SvgOptions {
+        highlights: vec![square("a8")],
+        ..SvgOptions::default()
+    };
+    let image: String = svg::to_svg(&board, &svg::with_last_move(&board, &options));
+    assert_eq!(image.matches("#f7ec5a").count(), 3);
+    assert!(image.contains(&highlight(20, 20)));
+    assert!(image.contains(&highlight(200, 290)));
+    assert!(image.contains(&highlight(200, 200)));
+
+    let options: SvgOptions = svg::with_last_move(&board, &black_at_the_bottom());
+    let image: String = svg::to_svg(&board, &options);
+    assert!(image.contains(&highlight(155, 65)));
+    assert!(image.contains(&highlight(155, 155)));
+}
@@ -0,0 +1,56 @@
+use chess::Timer;
+use std::thread;
+use std::time::Duration;
+
+#[test]
+fn a_running_clock_counts_down() {
+    let timer: Timer = Timer::new(60);
+    assert_eq!(timer.remaining_duration(), Duration::from_secs(60));
+    timer.countdown_start();
+    thread::sleep(Duration::from_millis(200));
+    let remaining: Duration = timer.remaining_duration();
+    assert!(remaining < Duration::from_secs(60));
+    assert!(remaining > Duration::from_secs(59));
+    assert!(timer.is_running());
+}
+
+#[test]
+fn a_paused_clock_keeps_its_time() {
+    let timer: Timer = Timer::new(60);
+    timer.countdown_start();
+    timer.pause();
+    thread::sleep(Duration::from_millis(50));
+    let paused: Duration = timer.remaining_duration();
+    thread::sleep(Duration::from_millis(200));
+    assert_eq!(timer.remaining_duration(), paused);
+    assert!(!timer.is_running());
+
+    timer.resume();
+    thread::sleep(Duration::from_millis(200));
+    assert!(timer.remaining_duration() < paused);
+}
+
+#[test]
+fn a_clock_runs_out() {
+    let timer: Timer = Timer::new(0);
+    timer.set_remaining(Duration::from_millis(100));
+    timer.countdown_start().join().unwrap();
+    assert!(timer.is_finished());
+    assert_eq!(timer.remaining_duration(), Duration::ZERO);
+}
+
+#[test]
+fn setting_the_time_of_a_finished_clock_leaves_it_stopped() {
+    let timer: Timer = Timer::new(0);
+    timer.countdown_start().join().unwrap();
+    timer.set_remaining(Duration::from_secs(60));
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(timer.remaining_duration(), Duration::from_secs(60));
+
+    // started again, it counts down at the normal speed
+    timer.countdown_start();
+    thread::sleep(Duration::from_millis(500));
+    let remaining: Duration = timer.remaining_duration();
+    assert!(remaining < Duration::from_millis(59_600));
+    assert!(remaining > Duration::from_millis(59_300));
+}
@@ -0,0 +1,191 @@
+mod common;
+
+use chess::save::SavedGame;
+use chess::variant::{self, ThreeCheck, Variant};
+use chess::{Board, GameManager, GameResult, PieceColour, Termination};
+use common::{scripted, silent};
+
+fn game(name: &str, fen: &str, white: &[&str], black: &[&str]) -> GameManager {
+    let (board, turn) = Board::from_fen(fen).unwrap();
+    let mut game: GameManager =
+        GameManager::from_position(5, board, turn, scripted(white), scripted(black));
+    game.set_renderer(silent());
+    game.set_variant(variant::from_name(name).unwrap());
+    game
+}
+
+const START: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+/// The white queen can check the black king three times in a row.
+const QUEEN_CHECKS: &str = "4k3/8/8/8/8/8/8/Q3K3 w - - 0 1";
+
+#[test]
+fn a_king_reaching_the_centre_wins_king_of_the_hill() {
+    let result: GameResult = game(
+        "king-of-the-hill",
+        START,
+        &["e4", "Ke2", "Ke3", "Kd4"],
+        &["a6", "a5", "a4", "a3"],
+    )
+    .start_game();
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.termination, Termination::CentreReached);
+    assert_eq!(result.to_string(), "White won by king reaching the centre");
+}
+
+#[test]
+fn the_third_check_wins_three_check() {
+    let mut game: GameManager = game(
+        "three-check",
+        QUEEN_CHECKS,
+        &["Qa4", "Qf4", "Qg4"],
+        &["Kf7", "Kg6", "Kh7"],
+    );
+    let result: GameResult = game.start_game();
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.termination, Termination::ThreeChecks);
+    assert_eq!(result.moves.len(), 5);
+    assert_eq!(
+        game.variant().note(game.board(), PieceColour::White),
+        "3 checks"
+    );
+    assert_eq!(
+        game.variant().note(game.board(), PieceColour::Black),
+        "0 checks"
+    );
+}
+
+#[test]
+fn three_check_keeps_the_checks_left_in_fen() {
+    let mut three_check: ThreeCheck = ThreeCheck::new();
+    let (board, turn) = three_check
+        .read_fen("4k3/8/8/8/8/8/8/Q3K3 w - - 1+3 0 1")
+        .unwrap();
+    assert_eq!(three_check.checks(PieceColour::White), 2);
+    assert_eq!(
+        three_check.to_fen(&board, turn, 1),
+        "4k3/8/8/8/8/8/8/Q3K3 w - - 1+3 0 1"
+    );
+    assert!(three_check
+        .read_fen("4k3/8/8/8/8/8/8/Q3K3 w - - 4+3 0 1")
+        .is_err());
+}
+
+#[test]
+fn a_variant_set_on_a_game_starts_afresh() {
+    let mut three_check: ThreeCheck = ThreeCheck::new();
+    three_check
+        .read_fen("4k3/8/8/8/8/8/8/Q3K3 w - - 1+3 0 1")
+        .unwrap();
+    let (board, turn) = Board::from_fen(QUEEN_CHECKS).unwrap();
+    let mut game: GameManager =
+        GameManager::from_position(5, board, turn, scripted(&["Qa4"]), scripted(&[]));
+    game.set_variant(Box::new(three_check));
+    assert_eq!(
+        game.variant().note(game.board(), PieceColour::White),
+        "0 checks"
+    );
+    assert_eq!(
+        game.saved_game().start,
+        "4k3/8/8/8/8/8/8/Q3K3 w - - 3+3 0 1"
+    );
+}
+
+#[test]
+fn adjourned_variant_games_resume_with_their_state() {
+    // White runs out of moves and resigns; the game so far is still there to save
+    let mut adjourned: GameManager = game(
+        "three-check",
+        QUEEN_CHECKS,
+        &["Qa4", "Qf4"],
+        &["Kf7", "Kg6"],
+    );
+    adjourned.start_game();
+    let saved: SavedGame = SavedGame::parse(&adjourned.saved_game().to_text()).unwrap();
+    assert_eq!(saved.variant.as_deref(), Some("Three-check"));
+    assert_eq!(saved.start, "4k3/8/8/8/8/8/8/Q3K3 w - - 3+3 0 1");
+    assert_eq!(saved.position, "8/8/6k1/8/5Q2/8/8/4K3 w - - 1+3 4 3");
+
+    let mut resumed: GameManager =
+        GameManager::resume(&saved, scripted(&["Qg4"]), scripted(&[])).unwrap();
+    resumed.set_renderer(silent());
+    let result: GameResult = resumed.start_game();
+    assert_eq!(result.termination, Termination::ThreeChecks);
+}
+
+#[test]
+fn variant_games_are_tagged_in_pgn() {
+    let mut game: GameManager = game("king-of-the-hill", START, &[], &[]);
+    game.start_game();
+    let pgn: String = game.pgn();
+    assert!(pgn.contains("[Variant \"King of the Hill\"]"));
+    assert!(!pgn.contains("[FEN"));
+}
+
+#[test]
+fn crazyhouse_games_play_drops_and_keep_the_pockets() {
+    let mut game: GameManager = game(
+        "crazyhouse",
+        START,
+        &["e4", "e4d5", "P@e4"],
+        &["d5", "d8d5"],
+    );
+    game.start_game();
+    assert_eq!(game.variant().note(game.board(), PieceColour::White), "");
+    assert_eq!(
+        game.variant().note(game.board(), PieceColour::Black),
+        "pocket P"
+    );
+    let pgn: String = game.pgn();
+    assert!(pgn.contains("[Variant \"Crazyhouse\"]"));
+    assert!(pgn.contains("3. P@e4"));
+
+    let saved: SavedGame = SavedGame::parse(&game.saved_game().to_text()).unwrap();
+    assert_eq!(
+        saved.position,
+        "rnb1kbnr/ppp1pppp/8/3q4/4P3/8/PPPP1PPP/RNBQKBNR[p] b KQkq - 0 3"
+    );
+    assert!(saved.replay().is_ok());
+}
+
+#[test]
+fn exploding_the_king_wins_atomic() {
+    let mut game: GameManager = game("atomic", "4k3/4p3/8/8/8/8/8/4RK2 w - - 0 1", &["e1e7"], &[]);
+    let result: GameResult = game.start_game();
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.termination, Termination::KingExploded);
+    assert_eq!(result.to_string(), "White won by exploding the king");
+    assert!(game.pgn().contains("1. Rxe7# 1-0"));
+}
+
+#[test]
+fn a_lone_king_can_still_win_king_of_the_hill() {
+    // taking the queen leaves the kings alone, which is no draw when White can reach d4
+    let result: GameResult = game(
+        "king-of-the-hill",
+        "4k3/8/8/8/8/8/3q4/4K3 w - - 0 1",
+        &["e1d2", "d2d3", "d3d4"],
+        &["e8f8", "f8g8"],
+    )
+    .start_game();
+    assert_eq!(result.winner, Some(PieceColour::White));
+    assert_eq!(result.termination, Termination::CentreReached);
+}
+
+#[test]
+fn a_lone_bishop_is_not_a_draw_in_three_check() {
+    // White gives check with the bishop and then resigns, having run out of moves
+    let result: GameResult = game(
+        "three-check",
+        "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+        &["f1b5"],
+        &["e8e7"],
+    )
+    .start_game();
+    assert_eq!(result.termination, Termination::Resignation);
+
+    let (board, _) = Board::from_fen("4k3/8/8/8/8/8/8/4KB2 w - - 0 1").unwrap();
+    assert!(variant::from_name("standard")
+        .unwrap()
+        .insufficient_material(&board));
+    assert!(!ThreeCheck::new().insufficient_material(&board));
+}
@@ -0,0 +1,77 @@
+use chess::xboard::XBoardSession;
+use chess::PieceColour;
+use std::io::Cursor;
+use std::time::Duration;
+
+/// Feeds `commands` to a fresh session, returning it with everything it wrote back.
+fn run(commands: &[&str]) -> (XBoardSession, Vec<String>) {
+    let mut session: XBoardSession = XBoardSession::new(5);
+    let mut output: Vec<u8> = Vec::new();
+    session
+        .run(Cursor::new(commands.join("\n")), &mut output)
+        .unwrap();
+    let lines: Vec<String> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    (session, lines)
+}
+
+fn engine_moves(lines: &[String]) -> usize {
+    lines
+        .iter()
+        .filter(|line| line.starts_with("move "))
+        .count()
+}
+
+#[test]
+fn the_engine_waits_in_force_mode_until_go() {
+    let (_, lines) = run(&["xboard", "new", "force", "e2e4", "e7e5"]);
+    assert_eq!(engine_moves(&lines), 0);
+    assert!(!lines.iter().any(|line| line.starts_with("Illegal")));
+
+    // go makes the engine play the side to move, and it answers every move after that
+    let (_, lines) = run(&["new", "force", "e2e4", "go", "quit"]);
+    assert_eq!(engine_moves(&lines), 1);
+    let (_, lines) = run(&["new", "e2e4"]);
+    assert_eq!(engine_moves(&lines), 1);
+}
+
+#[test]
+fn undo_takes_back_one_ply_and_remove_two() {
+    // after undo Black is to move again, so a different reply is legal
+    let (_, lines) = run(&["new", "force", "e2e4", "e7e5", "undo", "d7d5"]);
+    assert!(!lines.iter().any(|line| line.starts_with("Illegal")));
+
+    // after remove it is White's move from the start again
+    let (_, lines) = run(&["new", "force", "e2e4", "e7e5", "remove", "d2d4"]);
+    assert!(!lines.iter().any(|line| line.starts_with("Illegal")));
+    let (_, lines) = run(&["new", "force", "e2e4", "e7e5", "remove", "e7e5"]);
+    assert_eq!(lines, vec!["Illegal move: e7e5"]);
+}
+
+#[test]
+fn time_sets_the_engines_clock_and_otim_its_opponents() {
+    // the engine plays Black after new, and no clock runs in force mode
+    let (session, _) = run(&["new", "force", "time 6000", "otim 3000"]);
+    assert_eq!(
+        session.remaining(PieceColour::Black),
+        Duration::from_secs(60)
+    );
+    assert_eq!(
+        session.remaining(PieceColour::White),
+        Duration::from_secs(30)
+    );
+
+    // playother after e2e4 leaves the engine with White
+    let (session, lines) = run(&["new", "force", "e2e4", "playother", "force", "time 1000"]);
+    assert!(lines.is_empty());
+    assert_eq!(
+        session.remaining(PieceColour::White),
+        Duration::from_secs(10)
+    );
+
+    let (_, lines) = run(&["time soon"]);
+    assert_eq!(lines, vec!["Error (bad argument): time soon"]);
+}